@@ -0,0 +1,197 @@
+//! Derive macros for [`granite`]. Those are meant to be used through the `derive` feature of `granite`, which re-exports them, rather than by depending on this crate directly.
+//!
+//! [`granite`]: https://docs.rs/granite/*/granite/ " "
+
+#![warn(
+    rust_2018_idioms,
+    clippy::cargo,
+    clippy::nursery,
+    missing_docs,
+    unused_qualifications,
+    clippy::pedantic,
+    clippy::unwrap_used, // Only .expect() allowed
+)]
+#![allow(
+    clippy::use_self,
+    clippy::must_use_candidate,
+    clippy::module_name_repetitions
+)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input,
+    Attribute,
+    Data,
+    DeriveInput,
+    Error,
+    Field,
+    Fields,
+    Member,
+    Meta,
+    NestedMeta,
+    Result,
+};
+
+/// Derives `MoveFix` and `IndexField` for a struct or an enum whose fields store indices of other elements of the list containing it.
+///
+/// Fields marked with `#[movefix(index)]` are adjusted whenever the elements they point to get shifted or moved, while all other fields are ignored. The marked fields can be of any type which implements `IndexField` — `usize`, `Option`s, slices, arrays and `Vec`s of those, and other types deriving `MoveFix`. Arrays longer than those which `IndexField` is implemented for can be marked fields too, but only as the type of the field itself.
+///
+/// The derived implementation walks the whole storage for every notification, except for the batch ones (`fix_moves` and `fix_range_shift`), which are handled in a single pass. Indices which point to removed elements are left as they are, which makes them point to whatever element takes the place of the removed one.
+///
+/// Generic parameters don't receive any extra bounds, so if the type of a marked field depends on one of them, the required `IndexField` bound has to be specified manually.
+#[proc_macro_derive(MoveFix, attributes(movefix))]
+pub fn derive_move_fix(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_move_fix(&input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+fn expand_move_fix(input: &DeriveInput) -> Result<TokenStream2> {
+    reject_movefix_attrs(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, visits) = destructure(&quote!(Self), &data.fields)?;
+            quote! {
+                let #pattern = self;
+                #(#visits)*
+            }
+        }
+        Data::Enum(data) if data.variants.is_empty() => quote!(match *self {}),
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    reject_movefix_attrs(&variant.attrs)?;
+                    let ident = &variant.ident;
+                    let (pattern, visits) = destructure(&quote!(Self::#ident), &variant.fields)?;
+                    Ok(quote!(#pattern => { #(#visits)* }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "MoveFix cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // The storage type parameter is named so that it doesn't collide with the parameters of the type
+    Ok(quote! {
+        impl #impl_generics ::granite::IndexField for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn visit_indices(&mut self, f: &mut dyn ::core::ops::FnMut(&mut usize)) {
+                #[allow(unused_imports)]
+                use ::granite::IndexField as _;
+                #body
+            }
+        }
+        impl #impl_generics ::granite::MoveFix for #name #ty_generics #where_clause {
+            unsafe fn fix_shift<__Storage>(
+                storage: &mut __Storage,
+                shifted_from: usize,
+                shifted_by: ::core::num::NonZeroIsize,
+            ) where
+                __Storage: ::granite::ListStorage<Element = Self>,
+            {
+                ::granite::derive_support::fix_shift(storage, shifted_from, shifted_by)
+            }
+            unsafe fn fix_move<__Storage>(
+                storage: &mut __Storage,
+                previous_index: usize,
+                current_index: usize,
+            ) where
+                __Storage: ::granite::ListStorage<Element = Self>,
+            {
+                ::granite::derive_support::fix_move(storage, previous_index, current_index)
+            }
+            unsafe fn fix_moves<__Storage>(storage: &mut __Storage, moves: &[(usize, usize)])
+            where
+                __Storage: ::granite::ListStorage<Element = Self>,
+            {
+                ::granite::derive_support::fix_moves(storage, moves)
+            }
+            unsafe fn fix_range_shift<__Storage>(
+                storage: &mut __Storage,
+                range: ::core::ops::Range<usize>,
+                shifted_by: ::core::num::NonZeroIsize,
+            ) where
+                __Storage: ::granite::ListStorage<Element = Self>,
+            {
+                ::granite::derive_support::fix_range_shift(storage, range, shifted_by)
+            }
+        }
+    })
+}
+
+/// Builds a pattern which binds the marked fields of a struct or an enum variant, along with the statements visiting the indices in them.
+fn destructure(path: &TokenStream2, fields: &Fields) -> Result<(TokenStream2, Vec<TokenStream2>)> {
+    let mut members = Vec::new();
+    let mut bindings = Vec::new();
+    for (position, field) in fields.iter().enumerate() {
+        if !is_index(field)? {
+            continue;
+        }
+        members.push(
+            field
+                .ident
+                .clone()
+                .map_or_else(|| Member::Unnamed(position.into()), Member::Named),
+        );
+        bindings.push(format_ident!("__movefix_field_{}", position));
+    }
+    // Braced patterns work for tuple and unit structs too, which saves us from handling them separately
+    let pattern = quote!(#path { #(#members: #bindings,)* .. });
+    let visits = bindings
+        .iter()
+        .map(|binding| quote!(#binding.visit_indices(f);))
+        .collect();
+    Ok((pattern, visits))
+}
+
+/// Returns whether the field is marked with `#[movefix(index)]`.
+fn is_index(field: &Field) -> Result<bool> {
+    let mut index = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("movefix"))
+    {
+        match attr.parse_meta()? {
+            Meta::List(list)
+                if list.nested.len() == 1
+                    && matches!(
+                        list.nested.first(),
+                        Some(NestedMeta::Meta(Meta::Path(path))) if path.is_ident("index")
+                    ) =>
+            {
+                index = true;
+            }
+            meta => return Err(Error::new_spanned(meta, "expected `#[movefix(index)]`")),
+        }
+    }
+    Ok(index)
+}
+
+fn reject_movefix_attrs(attrs: &[Attribute]) -> Result<()> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("movefix"))
+        .map_or(Ok(()), |attr| {
+            Err(Error::new_spanned(
+                attr,
+                "`#[movefix]` attributes are only allowed on fields",
+            ))
+        })
+}
@@ -0,0 +1,91 @@
+//! The `MoveFix` hooks generated by `#[derive(MoveFix)]`. Not part of the public API.
+
+use core::{num::NonZeroIsize, ops::Range};
+use crate::{IndexField, ListStorage};
+
+/// Calls the closure for every index in every element of the storage, skipping holes.
+fn visit_all<S>(storage: &mut S, mut f: impl FnMut(&mut usize))
+where
+    S: ListStorage,
+    S::Element: IndexField,
+{
+    for index in 0..storage.len() {
+        if storage.contains_index(index) {
+            let element = unsafe {
+                // SAFETY: contains_index guarantees that this is safe
+                storage.get_unchecked_mut(index)
+            };
+            element.visit_indices(&mut f);
+        }
+    }
+}
+#[allow(clippy::cast_sign_loss)] // Wrapping addition of a negative shift in two's complement subtracts
+fn shift(index: &mut usize, shifted_by: NonZeroIsize) {
+    *index = index.wrapping_add(shifted_by.get() as usize);
+}
+
+#[allow(clippy::cast_sign_loss)] // Only cast when negative, subtracting it adds the amount of removed elements
+pub unsafe fn fix_shift<S>(storage: &mut S, shifted_from: usize, shifted_by: NonZeroIsize)
+where
+    S: ListStorage,
+    S::Element: IndexField,
+{
+    // Indices of removed elements are left alone instead of pointing before the removed range
+    let first_shifted = if shifted_by.get() < 0 {
+        shifted_from.wrapping_sub(shifted_by.get() as usize)
+    } else {
+        shifted_from
+    };
+    visit_all(storage, |index| {
+        if *index >= first_shifted {
+            shift(index, shifted_by);
+        }
+    });
+}
+pub unsafe fn fix_move<S>(storage: &mut S, previous_index: usize, current_index: usize)
+where
+    S: ListStorage,
+    S::Element: IndexField,
+{
+    visit_all(storage, |index| {
+        if *index == previous_index {
+            *index = current_index;
+        }
+    });
+}
+pub unsafe fn fix_moves<S>(storage: &mut S, moves: &[(usize, usize)])
+where
+    S: ListStorage,
+    S::Element: IndexField,
+{
+    // Without an allocator, there's nowhere to sort the moves, so they are searched linearly
+    #[cfg(feature = "alloc")]
+    let moves = &{
+        let mut sorted = moves.to_vec();
+        sorted.sort_unstable_by_key(|&(previous, _)| previous);
+        sorted
+    };
+    visit_all(storage, |index| {
+        #[cfg(feature = "alloc")]
+        let found = moves
+            .binary_search_by_key(index, |&(previous, _)| previous)
+            .ok()
+            .map(|position| moves[position]);
+        #[cfg(not(feature = "alloc"))]
+        let found = moves.iter().find(|&&(previous, _)| previous == *index).copied();
+        if let Some((_, current_index)) = found {
+            *index = current_index;
+        }
+    });
+}
+pub unsafe fn fix_range_shift<S>(storage: &mut S, range: Range<usize>, shifted_by: NonZeroIsize)
+where
+    S: ListStorage,
+    S::Element: IndexField,
+{
+    visit_all(storage, |index| {
+        if range.contains(&*index) {
+            shift(index, shifted_by);
+        }
+    });
+}
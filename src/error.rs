@@ -0,0 +1,41 @@
+use core::fmt::{self, Debug, Display, Formatter};
+
+/// The error type returned by fallible operations, such as `try_add` or `try_reserve`, when the storage does not have enough capacity and cannot reallocate.
+///
+/// The element which could not be added is handed back and can be retrieved with [`element`]. Operations which do not take an element, like `try_reserve`, use the default `()` as the element type.
+///
+/// [`element`]: #method.element " "
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CapacityError<E = ()> {
+    element: E,
+}
+impl<E> CapacityError<E> {
+    /// Creates a new capacity error from the element which could not be added.
+    pub const fn new(element: E) -> Self {
+        Self { element }
+    }
+    /// Extracts the element which could not be added.
+    #[allow(clippy::missing_const_for_fn)] // *sigh* destructors
+    pub fn element(self) -> E {
+        self.element
+    }
+    /// Converts the error into one which doesn't contain the element, dropping it.
+    pub fn simplify(self) -> CapacityError {
+        CapacityError::new(())
+    }
+    /// Converts the contained element with the specified closure, preserving the error.
+    pub fn map<T>(self, f: impl FnOnce(E) -> T) -> CapacityError<T> {
+        CapacityError::new(f(self.element))
+    }
+}
+static CAPACITY_ERROR_MSG: &str = "insufficient capacity";
+impl<E> Debug for CapacityError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "CapacityError: {}", CAPACITY_ERROR_MSG)
+    }
+}
+impl<E> Display for CapacityError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(CAPACITY_ERROR_MSG)
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Types of fields which [`#[derive(MoveFix)]`][derive] can adjust when they're marked with `#[movefix(index)]`: indices of elements in a list, or containers of such indices.
+///
+/// Implemented for `usize`, `Option`s, slices, arrays and `Vec`s of indices, and for all types which derive `MoveFix`, which visit all of their marked fields — this allows grouping indices into a separate struct.
+///
+/// Arrays only implement the trait for lengths of up to 32, since implementing it for all lengths requires const generics, which are newer than the minimum supported Rust version. Longer arrays of indices can still be marked fields themselves, since the derive macro visits them through slices, but cannot be nested into other containers, such as `Option<[usize; 64]>`.
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, MoveFix};
+///
+/// #[derive(MoveFix)]
+/// struct Node {
+///     value: u32,
+///     #[movefix(index)]
+///     next: Option<usize>,
+/// }
+/// fn values(list: &[Node]) -> Vec<u32> {
+///     let mut values = Vec::new();
+///     let mut next = Some(0);
+///     while let Some(index) = next {
+///         values.push(list[index].value);
+///         next = list[index].next;
+///     }
+///     values
+/// }
+///
+/// let mut list = Vec::new();
+/// for value in 0..5 {
+///     let next = if value < 4 { Some(value as usize + 1) } else { None };
+///     list.push(Node { value, next });
+/// }
+/// list.remove_and_shiftfix(0);
+/// assert_eq!(values(&list), [1, 2, 3, 4]);
+///
+/// // The link of the new head gets fixed too, since it points to a shifted element
+/// list.insert_and_shiftfix(0, Node { value: 9, next: Some(0) });
+/// assert_eq!(values(&list), [9, 1, 2, 3, 4]);
+/// ```
+///
+/// [derive]: derive.MoveFix.html " "
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "derive")))]
+pub trait IndexField {
+    /// Calls the specified closure for every index contained in the value, allowing it to modify them.
+    fn visit_indices(&mut self, f: &mut dyn FnMut(&mut usize));
+}
+impl IndexField for usize {
+    fn visit_indices(&mut self, f: &mut dyn FnMut(&mut usize)) {
+        f(self);
+    }
+}
+impl<T: IndexField> IndexField for Option<T> {
+    fn visit_indices(&mut self, f: &mut dyn FnMut(&mut usize)) {
+        if let Some(value) = self {
+            value.visit_indices(f);
+        }
+    }
+}
+impl<T: IndexField> IndexField for [T] {
+    fn visit_indices(&mut self, f: &mut dyn FnMut(&mut usize)) {
+        for value in self {
+            value.visit_indices(f);
+        }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: IndexField> IndexField for Vec<T> {
+    fn visit_indices(&mut self, f: &mut dyn FnMut(&mut usize)) {
+        self.as_mut_slice().visit_indices(f);
+    }
+}
+/// Implements the trait for arrays of the specified lengths.
+macro_rules! impl_for_arrays {
+    ($($len:literal)*) => {$(
+        impl<T: IndexField> IndexField for [T; $len] {
+            fn visit_indices(&mut self, f: &mut dyn FnMut(&mut usize)) {
+                self[..].visit_indices(f);
+            }
+        }
+    )*};
+}
+impl_for_arrays! {
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+    17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+}
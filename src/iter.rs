@@ -1,3 +1,5 @@
+use crate::Storage;
+
 /// Types which have corresponding immutably borrowing iterators.
 ///
 /// This is a lot like [`IntoIterator`], but analogous to [`Vec`]'s `.iter()` method.
@@ -20,3 +22,52 @@ pub trait IntoMutIterator<'a> {
     /// Borrows the value into a by-reference iterator with mutable access.
     fn iter_mut(&'a mut self) -> Self::IterMut;
 }
+/// Storages which can be iterated over, yielding the keys of the elements alongside them.
+///
+/// This allows generic data structures to walk all live elements of any storage, regardless of whether it is a list or a key-value map. The order of iteration is unspecified, though list storages iterate in index order.
+///
+/// Like [`IntoRefIterator`] and [`IntoMutIterator`], the trait is parametrized by the lifetime of the borrow, so generic code should use an HRTB bound like `for<'a> StorageIter<'a>`.
+///
+/// # Example
+/// ```rust
+/// use granite::{Storage, StorageIter, SparseVec, DummyMoveFix};
+///
+/// let mut storage = SparseVec::<DummyMoveFix<u32>>::new();
+/// let keys = [
+///     Storage::add(&mut storage, 0.into()),
+///     Storage::add(&mut storage, 1.into()),
+///     Storage::add(&mut storage, 2.into()),
+/// ];
+/// Storage::remove(&mut storage, &keys[1]);
+///
+/// // Holes are skipped when iterating:
+/// for (key, element) in StorageIter::iter_mut(&mut storage) {
+///     element.0 += key as u32;
+/// }
+/// let elements = storage.values().map(|x| x.0).collect::<Vec<_>>();
+/// assert_eq!(elements, [0, 4]);
+/// assert_eq!(storage.keys().collect::<Vec<_>>(), [0, 2]);
+/// ```
+pub trait StorageIter<'a>: Storage + 'a {
+    /// The iterator over the keys of the storage.
+    type Keys: Iterator<Item = Self::Key>;
+    /// The iterator over references to the elements of the storage.
+    type Values: Iterator<Item = &'a Self::Element>;
+    /// The iterator over mutable references to the elements of the storage.
+    type ValuesMut: Iterator<Item = &'a mut Self::Element>;
+    /// The iterator over keys and references to the elements of the storage.
+    type Iter: Iterator<Item = (Self::Key, &'a Self::Element)>;
+    /// The iterator over keys and mutable references to the elements of the storage.
+    type IterMut: Iterator<Item = (Self::Key, &'a mut Self::Element)>;
+
+    /// Returns an iterator over the keys of all elements in the storage.
+    fn keys(&'a self) -> Self::Keys;
+    /// Returns an iterator over references to all elements in the storage.
+    fn values(&'a self) -> Self::Values;
+    /// Returns an iterator over mutable references to all elements in the storage.
+    fn values_mut(&'a mut self) -> Self::ValuesMut;
+    /// Returns an iterator over the keys of all elements in the storage and references to those elements.
+    fn iter(&'a self) -> Self::Iter;
+    /// Returns an iterator over the keys of all elements in the storage and mutable references to those elements.
+    fn iter_mut(&'a mut self) -> Self::IterMut;
+}
@@ -15,6 +15,7 @@
 //! - `smallvec` — `^1.4`
 //! - `slab` — `^0.4`
 //! - `slotmap` — `^0.4`
+//! - `rayon` — `^1.5`
 //!
 //! PRs are welcome from those interested in those version numbers being modified.
 //!
@@ -24,8 +25,10 @@
 //! - `arrayvec` — enables support for [`ArrayVec`][ArrayVec0]. **You probably should use `tinyvec` instead.**
 //! - `smallvec` — enables support for [`SmallVec`]. **You probably should use `tinyvec` instead.**
 //! - `slab` — enables support for [`Slab`].
-//! - `slotmap` — enables support for [`SlotMap`], [`HopSlotMap`] and [`DenseSlotMap`]. *[`Slab`] will likely be faster because it's not versioned; this feature is largely here for compatibility.*
-//! - `union_optimizations` — forwarded to Granite, adds some layout optimizations by using untagged unions, decreasing memory usage in `SparseStorage`. **Requires a nightly compiler** (see [tracking issue for RFC 2514]) and thus is disabled by default.
+//! - `slotmap` — enables support for [`SlotMap`], [`HopSlotMap`] and [`DenseSlotMap`]. *[`Slab`] will likely be faster because it's not versioned; this feature is largely here for compatibility. To add versioning to any other storage, wrap it in [`Versioned`].*
+//! - `rayon` — enables parallel iteration over the elements of `Vec`, `VecDeque`, [`SparseStorage`] and [`Chain`] with [`rayon`], which requires the standard library. `Chain` distributes its storages between threads, which makes it especially suited for this.
+//! - `derive` — enables [`#[derive(MoveFix)]`][derive], which implements [`MoveFix`] for types storing indices of other elements in fields marked with `#[movefix(index)]`. See [`IndexField`] for details.
+//! - `union_optimizations` — does nothing and is only kept for backwards compatibility. The compact `SparseStorage` layout which it used to enable on nightly is now used on the stable compiler unconditionally.
 //!
 //! [`Vec`]: https://doc.rust-lang.org/std/vec/struct.Vec.html " "
 //! [`VecDeque`]: https://doc.rust-lang.org/std/collections/struct.VecDeque.html " "
@@ -38,11 +41,17 @@
 //! [`HopSlotMap`]: https://docs.rs/slotmap/*/slotmap/hop/struct.HopSlotMap.html " "
 //! [`DenseSlotMap`]: https://docs.rs/slotmap/*/slotmap/dense/struct.DenseSlotMap.html " "
 //! [`Slab`]: https://docs.rs/slab/*/slab/struct.Slab.html " "
+//! [`Versioned`]: struct.Versioned.html " "
+//! [`SparseStorage`]: struct.SparseStorage.html " "
+//! [`Chain`]: struct.Chain.html " "
+//! [derive]: derive.MoveFix.html " "
+//! [`MoveFix`]: trait.MoveFix.html " "
+//! [`IndexField`]: trait.IndexField.html " "
+//! [`rayon`]: https://docs.rs/rayon/*/rayon/ " "
 //! [`LinkedList`]: https://doc.rust-lang.org/std/collections/struct.LinkedList.html " "
 //! [`Rc`]: https://doc.rust-lang.org/std/rc/struct.Rc.html " "
 //! [`Arc`]: https://doc.rust-lang.org/std/sync/struct.Arc.html " "
 //! [DAG]: https://en.wikipedia.org/wiki/Directed_acyclic_graph " "
-//! [tracking issue for RFC 2514]: https://github.com/rust-lang/rust/issues/55149 " "
 
 #![warn(
     rust_2018_idioms,
@@ -76,6 +85,28 @@ pub use list::*;
 mod iter;
 pub use iter::*;
 
+#[cfg(feature = "rayon")]
+mod par_iter;
+#[cfg(feature = "rayon")]
+pub use par_iter::{IntoParRefIterator, IntoParMutIterator};
+
+#[cfg(feature = "derive")]
+mod index_field;
+#[cfg(feature = "derive")]
+pub use index_field::IndexField;
+#[cfg(feature = "derive")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "derive")))]
+pub use granite_derive::MoveFix;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod derive_support;
+
+mod versioned;
+pub use versioned::{Versioned, VersionedKey, VersionedElement};
+
+mod error;
+pub use error::CapacityError;
+
 #[cfg(feature = "slab")]
 mod slab_impl;
 #[cfg(feature = "slotmap")]
@@ -189,6 +220,43 @@ pub unsafe trait Storage: Sized {
             unimplemented!("this storage type does not support reallocation")
         }
     }
+    /// Adds an element to the collection with an unspecified key, returning that key. This is the fallible counterpart of `add`.
+    ///
+    /// The default implementation checks the length against [`CAPACITY`] if it's `Some(...)` and calls `add` if there's room left.
+    ///
+    /// # Errors
+    /// Returns the element back wrapped in a [`CapacityError`] if the storage is full and cannot reallocate.
+    ///
+    /// [`CapacityError`]: struct.CapacityError.html " "
+    /// [`CAPACITY`]: #associatedconstant.CAPACITY " "
+    fn try_add(&mut self, element: Self::Element) -> Result<Self::Key, CapacityError<Self::Element>> {
+        if Self::CAPACITY.map_or(false, |capacity| self.len() >= capacity) {
+            Err(CapacityError::new(element))
+        } else {
+            Ok(self.add(element))
+        }
+    }
+    /// Reserves capacity for at least `additional` more elements. This is the fallible counterpart of [`reserve`], and never panics due to insufficient capacity.
+    ///
+    /// The default implementation checks the resulting length against [`CAPACITY`] if it's `Some(...)` and calls `reserve` if it fits.
+    ///
+    /// # Errors
+    /// Returns an error if the storage cannot hold that many elements and cannot reallocate, or if the required capacity overflows `usize`.
+    ///
+    /// [`reserve`]: #method.reserve " "
+    /// [`CAPACITY`]: #associatedconstant.CAPACITY " "
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        let fits = self
+            .len()
+            .checked_add(additional)
+            .map_or(false, |required| Self::CAPACITY.map_or(true, |capacity| required <= capacity));
+        if fits {
+            self.reserve(additional);
+            Ok(())
+        } else {
+            Err(CapacityError::new(()))
+        }
+    }
     /// Shrinks the capacity of the storage as much as possible.
     ///
     /// It will drop down as close as possible to the current length, though dynamically allocated storages may not always reallocate exactly as much as it is needed to store all elements and none more.
@@ -205,6 +273,7 @@ pub unsafe trait Storage: Sized {
 /// - Otherwise, if `smallvec` is enabled, a [*sparse*][`SparseStorage`] [`SmallVec`] *with zero-sized backing storage* is used
 /// - Otherwise, if `arrayvec` is enabled, an [`ArrayVec`] *with zero-sized backing storage* is used
 /// - If even `arrayvec` is not available, a compile error is generated.
+///
 /// No other storage types are ever used as defaults.
 ///
 /// [`Slab`]: https://docs.rs/slab/*/slab/struct.Slab.html " "
@@ -218,7 +287,8 @@ pub type DefaultStorage<T> = _DefaultStorage<T>;
 type _DefaultStorage<T> = SparseVec<T>;
 
 #[cfg(all(feature = "smallvec", not(feature = "alloc")))]
-type _DefaultStorage<T> = SparseStorage<smallvec::SmallVec<[T; 0]>, T>;
+type _DefaultStorage<T> =
+    SparseStorage<T, smallvec::SmallVec<[SparseStorageSlot<T>; 0]>, smallvec::SmallVec<[usize; 0]>>;
 
 #[cfg(all(
     feature = "arrayvec",
@@ -3,7 +3,11 @@ use alloc::{
     vec::Vec,
     collections::vec_deque::{self, VecDeque},
 };
-use crate::{IntoMutIterator, IntoRefIterator, ListStorage};
+use crate::{IntoMutIterator, IntoRefIterator, ListStorage, DenseList};
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator};
+#[cfg(feature = "rayon")]
+use crate::{IntoParRefIterator, IntoParMutIterator};
 
 unsafe impl<T> ListStorage for Vec<T> {
     type Element = T;
@@ -12,7 +16,7 @@ unsafe impl<T> ListStorage for Vec<T> {
         Self::with_capacity(capacity)
     }
     fn insert(&mut self, index: usize, element: Self::Element) {
-        self.insert(index, element)
+        self.insert(index, element);
     }
     fn remove(&mut self, index: usize) -> Self::Element {
         self.remove(index)
@@ -37,7 +41,7 @@ unsafe impl<T> ListStorage for Vec<T> {
         Self::new()
     }
     fn push(&mut self, element: Self::Element) {
-        self.push(element)
+        self.push(element);
     }
     fn pop(&mut self) -> Option<Self::Element> {
         self.pop()
@@ -46,29 +50,36 @@ unsafe impl<T> ListStorage for Vec<T> {
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
-        self.shrink_to_fit()
+        self.shrink_to_fit();
     }
     fn truncate(&mut self, len: usize) {
-        self.truncate(len)
+        self.truncate(len);
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        self.split_off(at)
+    }
+    fn append(&mut self, other: &mut Self) {
+        self.append(other);
     }
 }
 impl<'a, T: 'a> IntoRefIterator<'a> for Vec<T> {
     type Item = T;
     type Iter = slice::Iter<'a, T>;
     fn iter(&'a self) -> Self::Iter {
-        (&self[..]).iter()
+        self[..].iter()
     }
 }
 impl<'a, T: 'a> IntoMutIterator<'a> for Vec<T> {
     type Item = T;
     type IterMut = slice::IterMut<'a, T>;
     fn iter_mut(&'a mut self) -> Self::IterMut {
-        (&mut self[..]).iter_mut()
+        self[..].iter_mut()
     }
 }
+unsafe impl<T> DenseList for Vec<T> {}
 
 unsafe impl<T> ListStorage for VecDeque<T> {
     type Element = T;
@@ -77,7 +88,7 @@ unsafe impl<T> ListStorage for VecDeque<T> {
         Self::with_capacity(capacity)
     }
     fn insert(&mut self, index: usize, element: Self::Element) {
-        self.insert(index, element)
+        self.insert(index, element);
     }
     fn remove(&mut self, index: usize) -> Self::Element {
         self.remove(index).expect("index out of bounds")
@@ -103,7 +114,7 @@ unsafe impl<T> ListStorage for VecDeque<T> {
         self.get_mut(index)
     }
     fn push(&mut self, element: Self::Element) {
-        self.push_back(element)
+        self.push_back(element);
     }
     fn pop(&mut self) -> Option<Self::Element> {
         self.pop_back()
@@ -112,13 +123,19 @@ unsafe impl<T> ListStorage for VecDeque<T> {
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
-        self.shrink_to_fit()
+        self.shrink_to_fit();
     }
     fn truncate(&mut self, len: usize) {
-        self.truncate(len)
+        self.truncate(len);
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        self.split_off(at)
+    }
+    fn append(&mut self, other: &mut Self) {
+        self.append(other);
     }
 }
 impl<'a, T: 'a> IntoRefIterator<'a> for VecDeque<T> {
@@ -135,6 +152,40 @@ impl<'a, T: 'a> IntoMutIterator<'a> for VecDeque<T> {
         self.iter_mut()
     }
 }
+unsafe impl<T> DenseList for VecDeque<T> {}
+
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync + 'a> IntoParRefIterator<'a> for Vec<T> {
+    type Item = T;
+    type ParIter = rayon::slice::Iter<'a, T>;
+    fn par_elements(&'a self) -> Self::ParIter {
+        self[..].par_iter()
+    }
+}
+#[cfg(feature = "rayon")]
+impl<'a, T: Send + 'a> IntoParMutIterator<'a> for Vec<T> {
+    type Item = T;
+    type ParIterMut = rayon::slice::IterMut<'a, T>;
+    fn par_elements_mut(&'a mut self) -> Self::ParIterMut {
+        self[..].par_iter_mut()
+    }
+}
+#[cfg(feature = "rayon")]
+impl<'a, T: Sync + 'a> IntoParRefIterator<'a> for VecDeque<T> {
+    type Item = T;
+    type ParIter = rayon::collections::vec_deque::Iter<'a, T>;
+    fn par_elements(&'a self) -> Self::ParIter {
+        self.par_iter()
+    }
+}
+#[cfg(feature = "rayon")]
+impl<'a, T: Send + 'a> IntoParMutIterator<'a> for VecDeque<T> {
+    type Item = T;
+    type ParIterMut = rayon::collections::vec_deque::IterMut<'a, T>;
+    fn par_elements_mut(&'a mut self) -> Self::ParIterMut {
+        self.par_iter_mut()
+    }
+}
 
 /*
 // TODO reimplement LinkedList with a custom SmartLinkedList
@@ -1,6 +1,6 @@
 use arrayvec::{ArrayVec, Array};
 use core::slice;
-use crate::{ListStorage, IntoRefIterator, IntoMutIterator};
+use crate::{ListStorage, IntoRefIterator, IntoMutIterator, DenseList, CapacityError};
 
 unsafe impl<A> ListStorage for ArrayVec<A>
 where
@@ -17,7 +17,7 @@ where
         Self::new()
     }
     fn insert(&mut self, index: usize, element: Self::Element) {
-        self.insert(index, element)
+        self.insert(index, element);
     }
     fn remove(&mut self, index: usize) -> Self::Element {
         self.remove(index)
@@ -42,7 +42,7 @@ where
         Self::new()
     }
     fn push(&mut self, element: Self::Element) {
-        self.push(element)
+        self.push(element);
     }
     fn pop(&mut self) -> Option<Self::Element> {
         self.pop()
@@ -57,7 +57,28 @@ where
     }
     fn shrink_to_fit(&mut self) {}
     fn truncate(&mut self, len: usize) {
-        self.truncate(len)
+        self.truncate(len);
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        self.drain(at..).collect()
+    }
+    fn append(&mut self, other: &mut Self) {
+        // Extending would silently drop the elements which don't fit
+        for element in other.drain(..) {
+            self.push(element);
+        }
+    }
+    fn try_insert(
+        &mut self,
+        index: usize,
+        element: Self::Element,
+    ) -> Result<(), CapacityError<Self::Element>> {
+        self.try_insert(index, element)
+            .map_err(|e| CapacityError::new(e.element()))
+    }
+    fn try_push(&mut self, element: Self::Element) -> Result<(), CapacityError<Self::Element>> {
+        self.try_push(element)
+            .map_err(|e| CapacityError::new(e.element()))
     }
 }
 impl<'a, A: Array> IntoRefIterator<'a> for ArrayVec<A>
@@ -80,3 +101,4 @@ where
         self.as_mut_slice().iter_mut()
     }
 }
+unsafe impl<A: Array> DenseList for ArrayVec<A> {}
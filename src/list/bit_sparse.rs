@@ -0,0 +1,327 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::Map,
+};
+use super::{
+    sparse::{Slot, Iter, IterMut, Indexed},
+    DenseList,
+    List,
+    ListStorage,
+    MoveFix,
+    SparseStorage,
+};
+use crate::{IntoRefIterator, IntoMutIterator, StorageIter, CapacityError};
+
+/// A `Vec` wrapped in [`BitSparseStorage`], with the occupancy bitmap also stored in a `Vec`.
+///
+/// [`BitSparseStorage`]: struct.BitSparseStorage.html " "
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "alloc")))]
+pub type Vec<T> = BitSparseStorage<T, alloc::vec::Vec<Slot<T>>, alloc::vec::Vec<usize>>;
+
+/// A variant of [`SparseStorage`] which keeps track of holes in its bitmap alone, without a hole list.
+///
+/// Elements are stored in slots which are exactly as big as the elements themselves, and a bit for every slot records whether it contains an element or is a hole. The bitmap has a second, summary level with a bit for every word of the first one, which is set if that word has at least one hole. This is the same layout as the one used by `SparseStorage`, and the storage is in fact a `SparseStorage` with its hole list turned off, which gives it the following properties:
+/// - `add` always finds the hole with the lowest index by skipping entire words of elements, so elements stay packed towards the start of the storage;
+/// - holes are never written to, and there are no hole links to maintain when elements are added, removed or shifted;
+/// - there is no hole reuse strategy or defragmentation policy to configure.
+///
+/// The backing storage of the bitmap is the third generic argument, so that the storage can be used without an allocator, e.g. with two `ArrayVec`s. Other than that, the storage behaves exactly like `SparseStorage` with the [`LowestIndex`] hole reuse strategy, and all methods which panic upon fetching a hole there panic here as well. The slots and iterators are those of `SparseStorage` too.
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, IntoRefIterator, BitSparseVec, DummyMoveFix};
+///
+/// let mut storage = BitSparseVec::<DummyMoveFix<u32>>::new();
+/// for value in 0..200 {
+///     storage.add(value.into());
+/// }
+/// // Punch lots of holes, leaving only every 64th element
+/// for index in 0..200 {
+///     if index % 64 != 0 {
+///         storage.remove_and_shiftfix(index);
+///     }
+/// }
+/// assert_eq!(storage.len(), 200);
+/// assert_eq!(storage.num_holes(), 196);
+/// assert!(storage.is_hole(1));
+/// assert_eq!(storage.try_get(1), None);
+/// // Iteration skips the holes a word at a time
+/// let indices = storage.iter().indexed().map(|(i, _)| i).collect::<Vec<_>>();
+/// assert_eq!(indices, [0, 64, 128, 192]);
+/// // Adding fills the first hole
+/// assert_eq!(storage.add(1000.into()), 1);
+///
+/// storage.defragment();
+/// assert!(storage.is_dense());
+/// let elements = storage.iter().map(|x| x.0).collect::<Vec<_>>();
+/// assert_eq!(elements, [0, 1000, 192, 128, 64]);
+/// ```
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`LowestIndex`]: enum.HoleReuse.html#variant.LowestIndex " "
+pub struct BitSparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    storage: SparseStorage<E, S, B>,
+}
+impl<E, S, B> BitSparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    /// Removes all holes from the storage, *without fixing elements' indices*. **This is an expensive operation and should only be called if `is_dense` is `false` to avoid needless overhead.**
+    pub fn defragment(&mut self) {
+        self.storage.defragment();
+    }
+    /// Removes all holes from the storage, fixing elements' indices. **This is an expensive operation and should only be called if `is_dense` is `false` to avoid needless overhead.**
+    ///
+    /// Holes are filled with elements from the end of the storage in a single pass, and every element which was relocated is reported exactly once through `MoveFix::fix_moves`, just like with [`SparseStorage`].
+    ///
+    /// [`SparseStorage`]: struct.SparseStorage.html#method.defragment_and_fix " "
+    pub fn defragment_and_fix(&mut self)
+    where
+        E: MoveFix,
+    {
+        self.storage.defragment_and_fix();
+    }
+    /// Returns `true` if the slot at the specified index is a hole, `false` if it contains an element or the index is out of bounds.
+    pub fn is_hole(&self, index: usize) -> bool {
+        self.storage.is_hole(index)
+    }
+    /// Returns a reference to the element at the specified index, or `None` if there's a hole there or the index is out of bounds. Unlike `get`, this never panics on holes.
+    pub fn try_get(&self, index: usize) -> Option<&E> {
+        self.storage.try_get(index)
+    }
+    /// Returns a mutable reference to the element at the specified index, or `None` if there's a hole there or the index is out of bounds. Unlike `get_mut`, this never panics on holes.
+    pub fn try_get_mut(&mut self, index: usize) -> Option<&mut E> {
+        self.storage.try_get_mut(index)
+    }
+    /// Returns the number of holes in the storage. This operation returns immediately instead of looping through the entire storage, since the storage tracks the number of holes it creates and destroys.
+    pub fn num_holes(&self) -> usize {
+        self.storage.num_holes()
+    }
+    /// Returns `true` if there are no holes in the storage, `false` otherwise. This operation returns immediately instead of looping through the entire storage, since the storage tracks the number of holes it creates and destroys.
+    pub fn is_dense(&self) -> bool {
+        self.storage.is_dense()
+    }
+
+    fn wrap(storage: SparseStorage<E, S, B>) -> Self {
+        Self {
+            storage: storage.into_unlinked(),
+        }
+    }
+}
+unsafe impl<E, S, B> ListStorage for BitSparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    type Element = E;
+    const CAPACITY: Option<usize> = S::CAPACITY;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self::wrap(SparseStorage::with_capacity(capacity))
+    }
+    fn insert(&mut self, index: usize, element: Self::Element) {
+        self.storage.insert(index, element);
+    }
+    // Will panic if a hole is encountered at the index.
+    #[track_caller]
+    fn remove(&mut self, index: usize) -> Self::Element {
+        self.storage.remove(index)
+    }
+    fn len(&self) -> usize {
+        self.storage.len()
+    }
+    // Will panic if a hole is encountered at the index.
+    unsafe fn get_unchecked(&self, index: usize) -> &Self::Element {
+        self.storage.get_unchecked(index)
+    }
+    // Will panic if a hole is encountered at the index.
+    unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut Self::Element {
+        self.storage.get_unchecked_mut(index)
+    }
+
+    fn contains_index(&self, index: usize) -> bool {
+        self.storage.contains_index(index)
+    }
+
+    // Will panic if a hole is encountered at the index.
+    #[track_caller]
+    fn get(&self, index: usize) -> Option<&Self::Element> {
+        self.storage.get(index)
+    }
+    // Will panic if a hole is encountered at the index.
+    #[track_caller]
+    fn get_mut(&mut self, index: usize) -> Option<&mut Self::Element> {
+        self.storage.get_mut(index)
+    }
+    fn new() -> Self {
+        Self::wrap(SparseStorage::new())
+    }
+    fn push(&mut self, element: Self::Element) {
+        self.storage.push(element);
+    }
+    // Discards the holes at the end of the storage to get to the last element.
+    fn pop(&mut self) -> Option<Self::Element> {
+        self.storage.pop()
+    }
+    fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+    fn reserve(&mut self, additional: usize) {
+        self.storage.reserve(additional);
+    }
+    fn shrink_to_fit(&mut self) {
+        self.storage.shrink_to_fit();
+    }
+    fn truncate(&mut self, len: usize) {
+        self.storage.truncate(len);
+    }
+    #[track_caller]
+    fn split_off(&mut self, at: usize) -> Self {
+        Self {
+            storage: self.storage.split_off(at),
+        }
+    }
+    fn append(&mut self, other: &mut Self) {
+        self.storage.append(&mut other.storage);
+    }
+    fn insert_and_shiftfix(&mut self, index: usize, element: Self::Element)
+    where
+        Self::Element: MoveFix,
+    {
+        self.storage.insert_and_shiftfix(index, element);
+    }
+    #[track_caller]
+    fn remove_and_shiftfix(&mut self, index: usize) -> Self::Element
+    where
+        Self::Element: MoveFix,
+    {
+        self.storage.remove_and_shiftfix(index)
+    }
+    fn add(&mut self, element: Self::Element) -> usize {
+        self.storage.add(element)
+    }
+    fn try_insert(
+        &mut self,
+        index: usize,
+        element: Self::Element,
+    ) -> Result<(), CapacityError<Self::Element>> {
+        self.storage.try_insert(index, element)
+    }
+    fn try_push(&mut self, element: Self::Element) -> Result<(), CapacityError<Self::Element>> {
+        self.storage.try_push(element)
+    }
+    fn try_add(&mut self, element: Self::Element) -> Result<usize, CapacityError<Self::Element>> {
+        self.storage.try_add(element)
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        self.storage.try_reserve(additional)
+    }
+}
+impl<E, S, B> Clone for BitSparseStorage<E, S, B>
+where
+    E: Clone,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize> + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage.clone(),
+        }
+    }
+}
+impl<E, S, B> Debug for BitSparseStorage<E, S, B>
+where
+    E: Debug,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.storage, f)
+    }
+}
+impl<E, S, B> Default for BitSparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E, S, B> PartialEq for BitSparseStorage<E, S, B>
+where
+    E: PartialEq,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.storage == other.storage
+    }
+}
+impl<E, S, B> Eq for BitSparseStorage<E, S, B>
+where
+    E: Eq,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E, S, B> IntoRefIterator<'a> for BitSparseStorage<E, S, B>
+where
+    E: 'a,
+    S: DenseList<Element = Slot<E>> + IntoRefIterator<'a, Item = Slot<E>>,
+    B: ListStorage<Element = usize> + 'a,
+{
+    type Item = E;
+    type Iter = Iter<'a, E, <S as IntoRefIterator<'a>>::Iter, B>;
+    /// Returns an iterator over references to the elements which skips holes. The index of each element can be retrieved by using the `indexed` method on the iterator.
+    fn iter(&'a self) -> Self::Iter {
+        IntoRefIterator::iter(&self.storage)
+    }
+}
+impl<'a, E, S, B> IntoMutIterator<'a> for BitSparseStorage<E, S, B>
+where
+    E: 'a,
+    S: DenseList<Element = Slot<E>> + IntoMutIterator<'a, Item = Slot<E>>,
+    B: ListStorage<Element = usize> + 'a,
+{
+    type Item = E;
+    type IterMut = IterMut<'a, E, <S as IntoMutIterator<'a>>::IterMut, B>;
+    /// Returns an iterator over mutable references to the elements which skips holes. The index of each element can be retrieved by using the `indexed` method on the iterator.
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        IntoMutIterator::iter_mut(&mut self.storage)
+    }
+}
+impl<'a, E, S, B> StorageIter<'a> for BitSparseStorage<E, S, B>
+where
+    E: MoveFix + 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+{
+    type Keys = Map<Indexed<Self::Values>, fn((usize, &'a E)) -> usize>;
+    type Values = Iter<'a, E, <S as IntoRefIterator<'a>>::Iter, B>;
+    type ValuesMut = IterMut<'a, E, <S as IntoMutIterator<'a>>::IterMut, B>;
+    type Iter = Indexed<Self::Values>;
+    type IterMut = Indexed<Self::ValuesMut>;
+
+    fn keys(&'a self) -> Self::Keys {
+        StorageIter::iter(self).map(|(index, _)| index)
+    }
+    fn values(&'a self) -> Self::Values {
+        IntoRefIterator::iter(self)
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        IntoMutIterator::iter_mut(self)
+    }
+    fn iter(&'a self) -> Self::Iter {
+        IntoRefIterator::iter(self).indexed()
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        IntoMutIterator::iter_mut(self).indexed()
+    }
+}
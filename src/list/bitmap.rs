@@ -0,0 +1,207 @@
+use core::mem::size_of;
+use crate::ListStorage;
+
+/// The number of bits in a word of the bitmap.
+pub(super) const BITS: usize = size_of::<usize>() * 8;
+
+/// Returns the number of words required to store the specified number of bits.
+const fn words_for(bits: usize) -> usize {
+    (bits + BITS - 1) / BITS
+}
+
+/// A two-level bitset.
+///
+/// The bottom level has one bit per slot, which is set if the slot contains an element. The summary level has one bit per word of the bottom level, which is set if that word has at least one clear bit, allowing to find the first hole while skipping over entire words of elements. Bits past the end are always kept clear.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct Bitmap<B> {
+    words: B,
+    summary: B,
+}
+impl<B> Bitmap<B>
+where
+    B: ListStorage<Element = usize>,
+{
+    pub(super) fn with_capacity(bits: usize) -> Self {
+        let words = words_for(bits);
+        Self {
+            words: B::with_capacity(words),
+            summary: B::with_capacity(words_for(words)),
+        }
+    }
+    pub(super) fn new() -> Self {
+        Self {
+            words: B::new(),
+            summary: B::new(),
+        }
+    }
+    /// Returns the word at the specified index, or zero if it's out of bounds.
+    fn word(&self, index: usize) -> usize {
+        self.words.get(index).copied().unwrap_or(0)
+    }
+    /// Returns the value of the bit at the specified index, or `false` if it's out of bounds.
+    pub(super) fn get(&self, index: usize) -> bool {
+        self.word(index / BITS) >> (index % BITS) & 1 != 0
+    }
+    /// Sets the bit at the specified index to the specified value.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds of the words, i.e. if `resize` wasn't called beforehand.
+    pub(super) fn set(&mut self, index: usize, value: bool) {
+        let (word_index, bit) = (index / BITS, index % BITS);
+        let word = self.word(word_index);
+        let word = if value {
+            word | 1 << bit
+        } else {
+            word & !(1 << bit)
+        };
+        self.set_word(word_index, word);
+    }
+    /// Overwrites the word at the specified index and updates the summary accordingly.
+    fn set_word(&mut self, index: usize, word: usize) {
+        *self.words.get_mut(index).expect(OUT_OF_BOUNDS_PANIC_MSG) = word;
+        self.sync_summary(index);
+    }
+    /// Updates the summary bit for the word at the specified index.
+    fn sync_summary(&mut self, index: usize) {
+        let not_full = self.word(index) != usize::MAX;
+        let summary_word = self
+            .summary
+            .get_mut(index / BITS)
+            .expect(OUT_OF_BOUNDS_PANIC_MSG);
+        if not_full {
+            *summary_word |= 1 << (index % BITS);
+        } else {
+            *summary_word &= !(1 << (index % BITS));
+        }
+    }
+    /// Reserves memory for the specified total amount of bits.
+    pub(super) fn reserve(&mut self, bits: usize) {
+        let words = words_for(bits);
+        self.words.reserve(words.saturating_sub(self.words.len()));
+        self.summary
+            .reserve(words_for(words).saturating_sub(self.summary.len()));
+    }
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.words.shrink_to_fit();
+        self.summary.shrink_to_fit();
+    }
+    /// Grows or shrinks the bitmap to exactly fit the specified amount of bits. New bits are clear, and the bits which are cut off are cleared.
+    pub(super) fn resize(&mut self, bits: usize) {
+        let old_words = self.words.len();
+        let new_words = words_for(bits);
+        while self.words.len() > new_words {
+            self.words.pop();
+        }
+        while self.words.len() < new_words {
+            self.words.push(0);
+        }
+        let new_summary_words = words_for(new_words);
+        while self.summary.len() > new_summary_words {
+            self.summary.pop();
+        }
+        while self.summary.len() < new_summary_words {
+            self.summary.push(0);
+        }
+        if new_words % BITS != 0 {
+            // Clear the summary bits of the words which were popped off
+            if let Some(last) = self.summary.get_mut(new_summary_words - 1) {
+                *last &= (1 << (new_words % BITS)) - 1;
+            }
+        }
+        if bits % BITS != 0 {
+            // Clear the bits which were cut off
+            if let Some(last) = self.words.get_mut(new_words - 1) {
+                *last &= (1 << (bits % BITS)) - 1;
+            }
+        }
+        for index in old_words.min(new_words).saturating_sub(1)..new_words {
+            self.sync_summary(index);
+        }
+    }
+    /// Inserts a bit at the specified index, shifting all bits after it towards higher indices. `len` is the number of bits before the insertion.
+    pub(super) fn insert(&mut self, index: usize, value: bool, len: usize) {
+        self.resize(len + 1);
+        let (first_word, bit) = (index / BITS, index % BITS);
+        let word = self.word(first_word);
+        let low_mask = (1 << bit) - 1;
+        let mut carry = word >> (BITS - 1);
+        let new_word = (word & low_mask) | ((word & !low_mask) << 1) | usize::from(value) << bit;
+        self.set_word(first_word, new_word);
+        for word_index in first_word + 1..self.words.len() {
+            let word = self.word(word_index);
+            self.set_word(word_index, word << 1 | carry);
+            carry = word >> (BITS - 1);
+        }
+    }
+    /// Removes the bit at the specified index, shifting all bits after it towards lower indices, and returns its value. `len` is the number of bits before the removal.
+    pub(super) fn remove(&mut self, index: usize, len: usize) -> bool {
+        let value = self.get(index);
+        let (first_word, bit) = (index / BITS, index % BITS);
+        let mut carry = 0;
+        for word_index in (first_word + 1..self.words.len()).rev() {
+            let word = self.word(word_index);
+            self.set_word(word_index, word >> 1 | carry << (BITS - 1));
+            carry = word & 1;
+        }
+        let word = self.word(first_word);
+        let low_mask = (1 << bit) - 1;
+        let new_word = (word & low_mask) | ((word >> 1) & !low_mask) | carry << (BITS - 1);
+        self.set_word(first_word, new_word);
+        self.resize(len - 1);
+        value
+    }
+    /// Returns the index of the first clear bit before `len`, using the summary to skip full words.
+    pub(super) fn first_clear(&self, len: usize) -> Option<usize> {
+        for summary_index in 0..self.summary.len() {
+            let summary_word = self.summary.get(summary_index).copied().unwrap_or(0);
+            if summary_word != 0 {
+                let word_index = summary_index * BITS + summary_word.trailing_zeros() as usize;
+                let index =
+                    word_index * BITS + (!self.word(word_index)).trailing_zeros() as usize;
+                // Only the last word can have clear bits past the end, so there are no clear bits
+                // before the end if this one isn't
+                return if index < len { Some(index) } else { None };
+            }
+        }
+        None
+    }
+    /// Returns the index of the first bit with the specified value in the range `from..end`, skipping words which don't have any such bits.
+    pub(super) fn next(&self, value: bool, from: usize, end: usize) -> Option<usize> {
+        let flip = if value { 0 } else { usize::MAX };
+        let mut word_index = from / BITS;
+        let mut word = (self.word(word_index) ^ flip) & usize::MAX << (from % BITS);
+        loop {
+            if word != 0 {
+                let index = word_index * BITS + word.trailing_zeros() as usize;
+                return if index < end { Some(index) } else { None };
+            }
+            word_index += 1;
+            if word_index * BITS >= end {
+                return None;
+            }
+            word = self.word(word_index) ^ flip;
+        }
+    }
+    /// Returns the index of the last bit with the specified value in the range `from..end`, skipping words which don't have any such bits.
+    pub(super) fn prev(&self, value: bool, from: usize, end: usize) -> Option<usize> {
+        if end <= from {
+            return None;
+        }
+        let flip = if value { 0 } else { usize::MAX };
+        let last = end - 1;
+        let mut word_index = last / BITS;
+        let mut word = (self.word(word_index) ^ flip) & usize::MAX >> (BITS - 1 - last % BITS);
+        loop {
+            if word != 0 {
+                let index = word_index * BITS + (BITS - 1 - word.leading_zeros() as usize);
+                return if index >= from { Some(index) } else { None };
+            }
+            if word_index == 0 || word_index * BITS <= from {
+                return None;
+            }
+            word_index -= 1;
+            word = self.word(word_index) ^ flip;
+        }
+    }
+}
+static OUT_OF_BOUNDS_PANIC_MSG: &str = "bitmap index out of bounds";
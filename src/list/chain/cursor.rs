@@ -0,0 +1,249 @@
+use core::fmt::{self, Debug, Formatter};
+use crate::{List, ListStorage, MoveFix};
+use super::{Chain, super::U_ONE};
+
+/// A cursor over a [`Chain`] with editing operations, for making sequential edits at a moving position.
+///
+/// This works just like [`ListCursorMut`], including the ghost position at the end, but keeps track of the storage which contains the current element and the index of the element inside of it. Moving the cursor within a storage and editing the chain at the cursor don't need to look up the storage like the index-based methods of the chain do — the lookup only happens when the cursor crosses into another storage or an edit splits or merges storages.
+///
+/// Created by the [`cursor_mut`] method.
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, Chain};
+///
+/// let mut chain = Chain::<u32, Vec<u32>, Vec<Vec<u32>>, Vec<usize>>::new();
+/// chain.set_limit(4);
+/// for value in 0..10 {
+///     chain.push(value);
+/// }
+/// let mut cursor = chain.cursor_mut(0);
+/// while let Some(&mut value) = cursor.current() {
+///     if value % 3 == 0 {
+///         cursor.remove_current();
+///     } else {
+///         cursor.insert_after(value * 10);
+///         cursor.move_next();
+///         cursor.move_next();
+///     }
+/// }
+/// assert_eq!(
+///     chain.elements().copied().collect::<Vec<_>>(),
+///     [1, 10, 2, 20, 4, 40, 5, 50, 7, 70, 8, 80],
+/// );
+/// ```
+///
+/// [`Chain`]: struct.Chain.html " "
+/// [`ListCursorMut`]: ../struct.ListCursorMut.html " "
+/// [`cursor_mut`]: struct.Chain.html#method.cursor_mut " "
+pub struct CursorMut<'a, T, S, I, P>
+where
+    S: List<Element = T>,
+    I: List<Element = S>,
+    P: ListStorage<Element = usize>,
+{
+    chain: &'a mut Chain<T, S, I, P>,
+    index: usize,
+    /// The storage and the index inside of it, `None` at the ghost position.
+    location: Option<(usize, usize)>,
+}
+impl<'a, T, S, I, P> CursorMut<'a, T, S, I, P>
+where
+    S: List<Element = T>,
+    I: List<Element = S>,
+    P: ListStorage<Element = usize>,
+{
+    #[track_caller]
+    pub(super) fn new(chain: &'a mut Chain<T, S, I, P>, index: usize) -> Self {
+        assert!(index <= chain.len, "index out of bounds");
+        let location = chain.locate(index);
+        Self {
+            chain,
+            index,
+            location,
+        }
+    }
+    /// Looks up the storage for the current index from scratch.
+    fn relocate(&mut self) {
+        if self.index > self.chain.len {
+            self.index = self.chain.len;
+        }
+        self.location = self.chain.locate(self.index);
+    }
+    /// Returns the index of the element the cursor points at, or `None` if it's at the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.location.map(|_| self.index)
+    }
+    /// Returns a mutable reference to the element the cursor points at, or `None` if it's at the ghost position or there's no element at its index, such as when the storages are [`SparseStorage`]s with a hole there.
+    ///
+    /// [`SparseStorage`]: ../struct.SparseStorage.html " "
+    pub fn current(&mut self) -> Option<&mut T> {
+        let (storage, offset) = self.location?;
+        let storage = self.chain.contents.get_mut(storage)?;
+        if !storage.contains_index(offset) {
+            return None;
+        }
+        storage.get_mut(offset)
+    }
+    /// Moves the cursor to the next element, or to the ghost position if it was at the last one, or to the first element if it was at the ghost position.
+    pub fn move_next(&mut self) {
+        if let Some((storage, offset)) = self.location {
+            self.index += 1;
+            if offset + 1 < self.chain.storage_len(storage) {
+                self.location = Some((storage, offset + 1));
+                return;
+            }
+        } else {
+            self.index = 0;
+        }
+        // Crossing into another storage, which might be preceded by empty ones
+        self.relocate();
+    }
+    /// Moves the cursor to the previous element, or to the ghost position if it was at the first one, or to the last element if it was at the ghost position.
+    pub fn move_prev(&mut self) {
+        match self.location {
+            Some((storage, offset)) if offset > 0 => {
+                self.index -= 1;
+                self.location = Some((storage, offset - 1));
+                return;
+            }
+            Some(_) if self.index == 0 => {
+                self.index = self.chain.len;
+                self.location = None;
+                return;
+            }
+            Some(_) => self.index -= 1,
+            None => self.index = self.chain.len.saturating_sub(1),
+        }
+        self.relocate();
+    }
+    /// Returns a shared reference to the chain the cursor is over.
+    pub fn as_chain(&self) -> &Chain<T, S, I, P> {
+        self.chain
+    }
+    /// Inserts an element before the one the cursor points at, or at the end of the chain if it's at the ghost position. The cursor keeps pointing at the same element.
+    pub fn insert_before(&mut self, element: T) {
+        match self.location {
+            Some((storage, offset)) if !self.chain.needs_split(storage) => {
+                self.chain
+                    .modify_storage(storage, |st| st.insert(offset, element));
+                self.location = Some((storage, offset + 1));
+                self.index += 1;
+            }
+            Some(_) => {
+                self.chain.insert(self.index, element);
+                self.index += 1;
+                self.relocate();
+            }
+            None => {
+                self.chain.insert(self.index, element);
+                self.index += 1;
+            }
+        }
+    }
+    /// Inserts an element after the one the cursor points at, or at the start of the chain if it's at the ghost position. The cursor keeps pointing at the same element.
+    pub fn insert_after(&mut self, element: T) {
+        match self.location {
+            Some((storage, offset)) if !self.chain.needs_split(storage) => {
+                self.chain
+                    .modify_storage(storage, |st| st.insert(offset + 1, element));
+            }
+            Some(_) => {
+                self.chain.insert(self.index + 1, element);
+                self.relocate();
+            }
+            None => {
+                self.chain.insert(0, element);
+                self.index += 1;
+            }
+        }
+    }
+    /// Removes the element the cursor points at and returns it, moving the cursor to the next element, or returns `None` if it's at the ghost position or there's no element at its index, like with [`current`].
+    ///
+    /// [`current`]: #method.current " "
+    pub fn remove_current(&mut self) -> Option<T> {
+        let (storage, offset) = self.location?;
+        if !self.chain.contents.get(storage)?.contains_index(offset) {
+            return None;
+        }
+        let num_storages = self.chain.contents.len();
+        let element = self.chain.modify_storage(storage, |st| st.remove(offset));
+        self.chain.merge_if_below_low_water_mark(storage);
+        // Unless the storage was merged or removed, the next element took the place of the removed one
+        if self.chain.contents.len() != num_storages || offset >= self.chain.storage_len(storage) {
+            self.relocate();
+        }
+        Some(element)
+    }
+    /// Same as [`insert_before`], but notifies the elements of the shift using [`MoveFix`], like `insert_and_shiftfix` does.
+    ///
+    /// [`insert_before`]: #method.insert_before " "
+    /// [`MoveFix`]: ../trait.MoveFix.html " "
+    pub fn insert_before_and_shiftfix(&mut self, element: T)
+    where
+        T: MoveFix,
+    {
+        let index = self.index;
+        self.insert_before(element);
+        self.fix_right_shift(index);
+    }
+    /// Same as [`insert_after`], but notifies the elements of the shift using [`MoveFix`], like `insert_and_shiftfix` does.
+    ///
+    /// [`insert_after`]: #method.insert_after " "
+    /// [`MoveFix`]: ../trait.MoveFix.html " "
+    pub fn insert_after_and_shiftfix(&mut self, element: T)
+    where
+        T: MoveFix,
+    {
+        let index = if self.location.is_some() {
+            self.index + 1
+        } else {
+            0
+        };
+        self.insert_after(element);
+        self.fix_right_shift(index);
+    }
+    /// Same as [`remove_current`], but notifies the elements of the shift using [`MoveFix`], like `remove_and_shiftfix` does.
+    ///
+    /// [`remove_current`]: #method.remove_current " "
+    /// [`MoveFix`]: ../trait.MoveFix.html " "
+    pub fn remove_current_and_shiftfix(&mut self) -> Option<T>
+    where
+        T: MoveFix,
+    {
+        let element = self.remove_current()?;
+        unsafe {
+            // SAFETY: the element was just removed from that index
+            T::fix_left_shift(self.chain, self.index, U_ONE);
+        }
+        // The notified elements can do whatever they want with the chain
+        self.relocate();
+        Some(element)
+    }
+    fn fix_right_shift(&mut self, index: usize)
+    where
+        T: MoveFix,
+    {
+        unsafe {
+            // SAFETY: an element was just inserted at that index
+            T::fix_right_shift(self.chain, index, U_ONE);
+        }
+        // Same as above
+        self.relocate();
+    }
+}
+impl<T, S, I, P> Debug for CursorMut<'_, T, S, I, P>
+where
+    S: List<Element = T>,
+    I: List<Element = S>,
+    P: ListStorage<Element = usize>,
+    Chain<T, S, I, P>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("chain::CursorMut")
+            .field("chain", &self.chain)
+            .field("index", &self.index)
+            .field("location", &self.location)
+            .finish()
+    }
+}
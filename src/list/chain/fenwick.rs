@@ -0,0 +1,124 @@
+use core::mem::size_of;
+use crate::{ListStorage, CapacityError};
+
+/// A Fenwick tree (binary indexed tree) over the lengths of the storages of a chain.
+///
+/// Node `i` (1-based) stores the sum of the lengths of the storages in the range `(i - lowbit(i), i]`, which makes both changing the length of a storage and finding the storage which contains an index take *O(log n)* time, where *n* is the number of storages. Appending a storage only reads the nodes which the new one covers, so pushing storages takes amortized *O(1)* time.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(super) struct Fenwick<P> {
+    nodes: P,
+}
+impl<P> Fenwick<P>
+where
+    P: ListStorage<Element = usize>,
+{
+    pub(super) fn new() -> Self {
+        Self { nodes: P::new() }
+    }
+    /// Returns the number of storages the tree keeps track of.
+    pub(super) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    /// Returns the node at the specified 1-based position.
+    fn node(&self, position: usize) -> usize {
+        debug_assert!(position != 0 && position <= self.len());
+        unsafe {
+            // SAFETY: all callers iterate positions within 1..=len
+            *self.nodes.get_unchecked(position - 1)
+        }
+    }
+    fn node_mut(&mut self, position: usize) -> &mut usize {
+        debug_assert!(position != 0 && position <= self.len());
+        unsafe {
+            // SAFETY: as above
+            self.nodes.get_unchecked_mut(position - 1)
+        }
+    }
+    /// Returns the sum of the lengths of the first `count` storages.
+    pub(super) fn prefix(&self, count: usize) -> usize {
+        let mut position = count;
+        let mut sum = 0;
+        while position != 0 {
+            sum += self.node(position);
+            position &= position - 1;
+        }
+        sum
+    }
+    /// Returns the node value which a storage with the specified length would have if it were appended.
+    fn node_for_push(&self, len: usize) -> usize {
+        // The new node covers the nodes which are found by repeatedly stripping the lowest bit off
+        // of the position before it, up until the start of its own range. There are as many of them
+        // as there are trailing zeros in the new position, which is 1 on average.
+        let position = self.len() + 1;
+        let covered_from = position & (position - 1);
+        let mut sum = len;
+        let mut child = position - 1;
+        while child > covered_from {
+            sum += self.node(child);
+            child &= child - 1;
+        }
+        sum
+    }
+    /// Appends a storage with the specified length.
+    pub(super) fn push(&mut self, len: usize) {
+        let node = self.node_for_push(len);
+        self.nodes.push(node);
+    }
+    /// Appends a storage with the specified length. This is the fallible counterpart of `push`.
+    pub(super) fn try_push(&mut self, len: usize) -> Result<(), CapacityError> {
+        let node = self.node_for_push(len);
+        self.nodes.try_push(node).map_err(CapacityError::simplify)
+    }
+    /// Forgets the last storage. The nodes of the storages before it never cover it, so they stay valid.
+    pub(super) fn pop(&mut self) {
+        self.nodes.pop();
+    }
+    /// Forgets the storages starting from the specified index, for the same reason as `pop`.
+    pub(super) fn truncate(&mut self, len: usize) {
+        self.nodes.truncate(len);
+    }
+    pub(super) fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        self.nodes.try_reserve(additional)
+    }
+    pub(super) fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+    }
+    /// Adjusts the length of the storage at the specified index from `old_len` to `new_len`.
+    pub(super) fn update(&mut self, index: usize, old_len: usize, new_len: usize) {
+        if old_len == new_len {
+            return;
+        }
+        let mut position = index + 1;
+        while position <= self.len() {
+            let node = self.node_mut(position);
+            // Nodes never underflow since they're sums of lengths which include the old one
+            *node = *node - old_len + new_len;
+            position += position & position.wrapping_neg();
+        }
+    }
+    /// Finds the storage which contains the element at the specified index, returning the index of the storage and the index of the element inside of it. Storages of length zero are skipped. Returns `None` if the index is not less than the total length.
+    pub(super) fn find(&self, index: usize) -> Option<(usize, usize)> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        // Descend from the highest power of two not greater than the amount of nodes, ending up at
+        // the amount of storages which all lie entirely before the index
+        let mut step = 1 << (size_of::<usize>() * 8 - 1 - len.leading_zeros() as usize);
+        let mut position = 0;
+        let mut remaining = index;
+        while step != 0 {
+            let next = position + step;
+            if next <= len && self.node(next) <= remaining {
+                position = next;
+                remaining -= self.node(next);
+            }
+            step >>= 1;
+        }
+        if position < len {
+            Some((position, remaining))
+        } else {
+            None
+        }
+    }
+}
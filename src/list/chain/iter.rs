@@ -109,8 +109,8 @@ impl<'a: 'b, 'b, S: List> IntoRefIterator<'b> for StorageProxy<'a, S> {
         self.0.iter()
     }
 }
-impl<'a, S: List> Copy for StorageProxy<'a, S> {}
-impl<'a, S: List> Clone for StorageProxy<'a, S> {
+impl<S: List> Copy for StorageProxy<'_, S> {}
+impl<S: List> Clone for StorageProxy<'_, S> {
     fn clone(&self) -> Self {
         *self
     }
@@ -141,3 +141,208 @@ where
         f.debug_tuple("StorageProxyMut").field(&self.0).finish()
     }
 }
+
+/// Adds up the size hints of the iterators over the storages of a chain.
+pub(super) fn sum_size_hints(
+    hints: impl Iterator<Item = (usize, Option<usize>)>,
+) -> (usize, Option<usize>) {
+    hints.fold((0, Some(0)), |(lower, upper), (st_lower, st_upper)| {
+        (
+            lower.saturating_add(st_lower),
+            upper.and_then(|upper| upper.checked_add(st_upper?)),
+        )
+    })
+}
+/// Updates a size hint after an element was yielded.
+fn yielded_one((lower, upper): (usize, Option<usize>)) -> (usize, Option<usize>) {
+    (lower.saturating_sub(1), upper.map(|upper| upper.saturating_sub(1)))
+}
+
+/// An iterator over references to the elements of a [`Chain`], created by [`Chain::elements`] or [`IntoRefIterator`].
+///
+/// [`Chain::elements`]: struct.Chain.html#method.elements " "
+pub struct Elements<'a, S: List + 'a, I: List<Element = S>> {
+    storages: <I as IntoRefIterator<'a>>::Iter,
+    front: Option<<S as IntoRefIterator<'a>>::Iter>,
+    back: Option<<S as IntoRefIterator<'a>>::Iter>,
+    /// The sum of the size hints of the iterators over the storages, kept separately from the chain length since storages with holes yield fewer elements than their length.
+    remaining: (usize, Option<usize>),
+}
+impl<'a, S: List + 'a, I: List<Element = S>> Elements<'a, S, I> {
+    pub(super) fn new(storages: <I as IntoRefIterator<'a>>::Iter, remaining: (usize, Option<usize>)) -> Self {
+        Self {
+            storages,
+            front: None,
+            back: None,
+            remaining,
+        }
+    }
+}
+impl<'a, S: List + 'a, I: List<Element = S>> Iterator for Elements<'a, S, I> {
+    type Item = &'a S::Element;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(element) = self.front.as_mut().and_then(Iterator::next) {
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+            if let Some(storage) = self.storages.next() {
+                self.front = Some(storage.iter());
+            } else {
+                // The back iterator might still have elements which the front one never got to
+                let element = self.back.as_mut()?.next()?;
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.remaining
+    }
+}
+impl<'a, S: List + 'a, I: List<Element = S>> DoubleEndedIterator for Elements<'a, S, I>
+where
+    <I as IntoRefIterator<'a>>::Iter: DoubleEndedIterator,
+    <S as IntoRefIterator<'a>>::Iter: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(element) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+            if let Some(storage) = self.storages.next_back() {
+                self.back = Some(storage.iter());
+            } else {
+                let element = self.front.as_mut()?.next_back()?;
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+        }
+    }
+}
+impl<'a, S: List + 'a, I: List<Element = S>> ExactSizeIterator for Elements<'a, S, I> where
+    <S as IntoRefIterator<'a>>::Iter: ExactSizeIterator
+{
+}
+impl<'a, S: List + 'a, I: List<Element = S>> FusedIterator for Elements<'a, S, I>
+where
+    <I as IntoRefIterator<'a>>::Iter: FusedIterator,
+    <S as IntoRefIterator<'a>>::Iter: FusedIterator,
+{
+}
+impl<'a, S: List + 'a, I: List<Element = S>> Clone for Elements<'a, S, I>
+where
+    <I as IntoRefIterator<'a>>::Iter: Clone,
+    <S as IntoRefIterator<'a>>::Iter: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storages: self.storages.clone(),
+            front: self.front.clone(),
+            back: self.back.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+impl<'a, S: List + 'a, I: List<Element = S>> Debug for Elements<'a, S, I>
+where
+    <I as IntoRefIterator<'a>>::Iter: Debug,
+    <S as IntoRefIterator<'a>>::Iter: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("chain::Elements")
+            .field("storages", &self.storages)
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An iterator over mutable references to the elements of a [`Chain`], created by [`Chain::elements_mut`] or [`IntoMutIterator`].
+///
+/// [`Chain::elements_mut`]: struct.Chain.html#method.elements_mut " "
+pub struct ElementsMut<'a, S: List + 'a, I: List<Element = S>> {
+    storages: <I as IntoMutIterator<'a>>::IterMut,
+    front: Option<<S as IntoMutIterator<'a>>::IterMut>,
+    back: Option<<S as IntoMutIterator<'a>>::IterMut>,
+    /// The sum of the size hints of the iterators over the storages, kept separately from the chain length since storages with holes yield fewer elements than their length.
+    remaining: (usize, Option<usize>),
+}
+impl<'a, S: List + 'a, I: List<Element = S>> ElementsMut<'a, S, I> {
+    pub(super) fn new(storages: <I as IntoMutIterator<'a>>::IterMut, remaining: (usize, Option<usize>)) -> Self {
+        Self {
+            storages,
+            front: None,
+            back: None,
+            remaining,
+        }
+    }
+}
+impl<'a, S: List + 'a, I: List<Element = S>> Iterator for ElementsMut<'a, S, I> {
+    type Item = &'a mut S::Element;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(element) = self.front.as_mut().and_then(Iterator::next) {
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+            if let Some(storage) = self.storages.next() {
+                self.front = Some(storage.iter_mut());
+            } else {
+                let element = self.back.as_mut()?.next()?;
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.remaining
+    }
+}
+impl<'a, S: List + 'a, I: List<Element = S>> DoubleEndedIterator for ElementsMut<'a, S, I>
+where
+    <I as IntoMutIterator<'a>>::IterMut: DoubleEndedIterator,
+    <S as IntoMutIterator<'a>>::IterMut: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(element) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+            if let Some(storage) = self.storages.next_back() {
+                self.back = Some(storage.iter_mut());
+            } else {
+                let element = self.front.as_mut()?.next_back()?;
+                self.remaining = yielded_one(self.remaining);
+                return Some(element);
+            }
+        }
+    }
+}
+impl<'a, S: List + 'a, I: List<Element = S>> ExactSizeIterator for ElementsMut<'a, S, I> where
+    <S as IntoMutIterator<'a>>::IterMut: ExactSizeIterator
+{
+}
+impl<'a, S: List + 'a, I: List<Element = S>> FusedIterator for ElementsMut<'a, S, I>
+where
+    <I as IntoMutIterator<'a>>::IterMut: FusedIterator,
+    <S as IntoMutIterator<'a>>::IterMut: FusedIterator,
+{
+}
+impl<'a, S: List + 'a, I: List<Element = S>> Debug for ElementsMut<'a, S, I>
+where
+    <I as IntoMutIterator<'a>>::IterMut: Debug,
+    <S as IntoMutIterator<'a>>::IterMut: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("chain::ElementsMut")
+            .field("storages", &self.storages)
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
@@ -2,13 +2,41 @@
 //!
 //! See the [struct-level documentation][Chain] for more.
 
-use core::{hint, mem};
-use crate::{List, ListStorage, MoveFix};
+use core::{
+    hint,
+    iter::repeat,
+    mem::{self, size_of},
+    num::NonZeroIsize,
+    ops::{Bound, RangeBounds},
+};
+use crate::{
+    List,
+    ListStorage,
+    DenseList,
+    MoveFix,
+    IntoRefIterator,
+    IntoMutIterator,
+    CapacityError,
+};
+#[cfg(feature = "rayon")]
+use crate::{IntoParRefIterator, IntoParMutIterator};
 
+mod fenwick;
+use fenwick::Fenwick;
 mod usize_and_flag;
 use usize_and_flag::UsizeAndFlag;
 mod iter;
 pub use iter::*;
+mod cursor;
+pub use cursor::CursorMut;
+mod sparse;
+pub use sparse::{SparseChain, SparseChainKey, SparseChainIter, SparseChainIterMut};
+#[cfg(feature = "alloc")]
+pub use sparse::Vec as SparseChainVec;
+#[cfg(feature = "rayon")]
+mod par_iter;
+#[cfg(feature = "rayon")]
+pub use par_iter::*;
 
 /// A list data structure wrapping a list of lists used to prevent lag spikes when dealing with huge numbers of elements.
 ///
@@ -17,23 +45,57 @@ pub use iter::*;
 /// Insertion problems can be partially solved by wrapping the `Vec` in a `SparseStorage` and its `insert_and_shiftfix`, but only if `remove_and_shiftfix` is also used at least exactly as much as `insert_and_shiftfix`. Insertions without removals and full reallocations, however, are left unsolved.
 ///
 /// `Chain` resolves the issue by limiting the capacity to which a single list storage can be used (technically doesn't have to be `Vec`, though that's what makes the most sense to use), allocating small buffers anew without touching bigger old ones if more space is requested.
+///
+/// To find the storage which contains an element without walking over all storages before it, the chain keeps a [Fenwick tree] of the lengths of its storages in a list storage of `usize`, which is the last generic argument. This makes accessing, inserting and removing elements take *O(log n)* time on top of the time it takes to do so in a single storage, where *n* is the number of storages. Pushing to the end takes amortized *O(1)* time as long as the last storage holds the last element, since the tree doesn't have to be searched for it then and updating the length of the last storage only touches one node. Empty storages left over from `reserve`, `truncate` or removing the elements at the end make such pushes take *O(log n)* time until they are filled.
+///
+/// When an element is inserted into a storage which is already filled up to the limit, the storage is split in two, so that storages never grow past the limit and insertions into the middle of the chain only ever shift at most `limit` elements. Conversely, a storage which falls below the [low-water mark] after a removal is merged with a neighboring one if they fit into the limit together, so that removals don't leave behind lots of nearly empty storages. A storage which becomes empty is removed, unless there are no elements after it, in which case it's kept as reserved capacity for subsequent pushes. Splitting, merging or removing a storage only has to rebuild the part of the Fenwick tree which comes after it, taking *O(n - i)* time, where *i* is the index of the storage. Elements are moved between storages in a single pass with the `split_off` and `append` methods of `ListStorage`, and the storage split off from a full one is only allocated to fit the elements moved into it. Sparse storages move their holes along with their elements, so the storages of the chain can also be sparse, like they are in [`SparseChain`]: holes count as elements of the chain, and splitting or merging storages never changes the index of an element or a hole in the whole chain.
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, Chain};
+///
+/// let mut chain = Chain::<u32, Vec<u32>, Vec<Vec<u32>>, Vec<usize>>::new();
+/// chain.set_limit(4);
+/// for value in 0..10 {
+///     chain.push(value);
+/// }
+/// assert_eq!(chain.num_storages(), 3);
+/// assert_eq!(chain.get(9), Some(&9));
+/// // The first storage is full, so it gets split in two
+/// chain.insert(2, 100);
+/// assert_eq!(chain.num_storages(), 4);
+/// chain.remove(0);
+/// assert_eq!(chain.get(1), Some(&100));
+/// assert_eq!(chain.get(9), Some(&9));
+/// assert_eq!(chain.get(10), None);
+/// ```
+///
+/// [Fenwick tree]: https://en.wikipedia.org/wiki/Fenwick_tree " "
+/// [low-water mark]: #method.set_low_water_mark " "
+/// [`SparseChain`]: struct.SparseChain.html " "
 #[derive(Copy, Clone, Debug)]
-pub struct Chain<T, S, I>
+pub struct Chain<T, S, I, P>
 where
     I: List<Element = S>,
     S: List<Element = T>,
+    P: ListStorage<Element = usize>,
 {
     contents: I,
+    lengths: Fenwick<P>,
     len: usize,
     limit: UsizeAndFlag,
+    /// `None` means a quarter of the limit.
+    low_water_mark: Option<usize>,
+    split_on_insert: bool,
 }
-impl<T, S, I> Chain<T, S, I>
+impl<T, S, I, P> Chain<T, S, I, P>
 where
     S: List<Element = T>,
     I: List<Element = S>,
+    P: ListStorage<Element = usize>,
 {
     const DEFAULT_LIMIT: usize = {
-        let base = 2048 / mem::size_of::<T>();
+        let base = 2048 / size_of::<T>();
         if base < 2 {
             // Cannot have less than 2 here, since the value has to be a
             // multiple of 2 due to UsizeAndFlag repurposing the lowest bit.
@@ -46,34 +108,132 @@ where
 
     /// Creates an iterator over references to the storages of the chain.
     ///
-    /// This allows for efficient iteration over the elements of a chain, especially if the index collection type (the list which contains the individual sub-lists) is a linked list or any other data structure with O(n) indexing. However, this requires an explicit nested loop, since creating an iterator struct which would flatten iterators into one contiguous iteration sequence is impossible, because that would require modelling a self-referential struct with lifetimes, which isn't currently possible.
+    /// This allows for working with the storages of the chain one by one, with an explicit nested loop. To simply iterate over the elements, use [`elements`] instead.
     ///
     /// Also, the iterator doesn't actually iterate over references to storages: it iterates over [*proxies*](StorageProxy) instead, which are wrappers around references to storages with the sole purpose of making all other trait implementations and methods inaccessible to make sure the chain upholds the `ListStorage` safety contract in the presence of interior mutability in the used storage type.
+    ///
+    /// [`elements`]: #method.elements " "
+    #[allow(clippy::iter_without_into_iter)] // Iterates over storages, not elements
     pub fn iter(&self) -> Iter<'_, S, I> {
         Iter(self.contents.iter())
     }
+    /// Creates an iterator over references to the elements of the chain, crossing storage boundaries seamlessly.
+    ///
+    /// This is also what the `IntoRefIterator` implementation returns, which makes the chain a [`List`] in its own right, so it can be used as the backing storage of other storages and data structures. Since the inherent `iter` method iterates over storages instead, that implementation needs to be called as `IntoRefIterator::iter(&chain)`.
+    ///
+    /// Creating the iterator takes *O(n)* time, where *n* is the number of storages, since it adds up the size hints of the iterators over the storages rather than trusting the length of the chain, which counts the holes if the storages have any. The iterator is an `ExactSizeIterator` if the iterators over the storages are.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, Chain};
+    ///
+    /// let mut chain = Chain::<u32, Vec<u32>, Vec<Vec<u32>>, Vec<usize>>::new();
+    /// chain.set_limit(2);
+    /// for value in 0..5 {
+    ///     chain.push(value);
+    /// }
+    /// let elements = chain.elements();
+    /// assert_eq!(elements.len(), 5);
+    /// assert_eq!(elements.rev().copied().collect::<Vec<_>>(), [4, 3, 2, 1, 0]);
+    /// for element in chain.elements_mut() {
+    ///     *element *= 10;
+    /// }
+    /// assert_eq!(chain.elements().copied().collect::<Vec<_>>(), [0, 10, 20, 30, 40]);
+    /// ```
+    ///
+    /// [`List`]: trait.List.html " "
+    pub fn elements(&self) -> Elements<'_, S, I> {
+        let remaining = sum_size_hints(self.contents.iter().map(|st| st.iter().size_hint()));
+        Elements::new(self.contents.iter(), remaining)
+    }
+    /// Creates an iterator over mutable references to the elements of the chain, crossing storage boundaries seamlessly.
+    ///
+    /// This is the mutable counterpart of [`elements`].
+    ///
+    /// [`elements`]: #method.elements " "
+    pub fn elements_mut(&mut self) -> ElementsMut<'_, S, I> {
+        let remaining =
+            sum_size_hints(self.contents.iter_mut().map(|st| st.iter_mut().size_hint()));
+        ElementsMut::new(self.contents.iter_mut(), remaining)
+    }
+    /// Creates a parallel iterator over references to the elements of the chain.
+    ///
+    /// The storages are the unit of work: they are distributed between the threads of the `rayon` thread pool, and every storage is walked sequentially by the thread which picked it up. This requires the list of storages to be iterable in parallel, which is the case for `Vec` and `VecDeque`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rayon::iter::ParallelIterator;
+    /// use granite::{ListStorage, Chain};
+    ///
+    /// let mut chain = Chain::<u64, Vec<u64>, Vec<Vec<u64>>, Vec<usize>>::new();
+    /// chain.set_limit(64);
+    /// for value in 0..10_000 {
+    ///     chain.push(value);
+    /// }
+    /// chain.par_elements_mut().for_each(|value| *value *= 3);
+    /// let sum: u64 = chain.par_elements().sum();
+    /// assert_eq!(sum, (0..10_000).map(|value| value * 3).sum());
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+    pub fn par_elements<'a>(&'a self) -> ParElements<'a, S, I>
+    where
+        T: Sync,
+        S: Sync + 'a,
+        I: IntoParRefIterator<'a, Item = S>,
+    {
+        ParElements::new(self.contents.par_elements())
+    }
+    /// Creates a parallel iterator over mutable references to the elements of the chain.
+    ///
+    /// This is the mutable counterpart of [`par_elements`].
+    ///
+    /// [`par_elements`]: #method.par_elements " "
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+    pub fn par_elements_mut<'a>(&'a mut self) -> ParElementsMut<'a, S, I>
+    where
+        T: Send,
+        S: Send + 'a,
+        I: IntoParMutIterator<'a, Item = S>,
+    {
+        ParElementsMut::new(self.contents.par_elements_mut())
+    }
     /// Creates an iterator over mutable references to the storages of the chain.
     ///
     /// This is the same as [`iter`] — so keep all considerations which apply to it in mind — but the [proxies](StorageProxyMut) wrap mutable references and not immutable ones.
+    #[allow(clippy::iter_without_into_iter)] // Same here
     pub fn iter_mut(&mut self) -> IterMut<'_, S, I> {
         IterMut(self.contents.iter_mut())
     }
+    /// Creates a cursor pointing at the element at the specified index, or at the ghost position if the index is equal to the length of the chain. See [`CursorMut`] for more.
+    ///
+    /// # Panics
+    /// Panics if the index is greater than the length of the chain.
+    ///
+    /// [`CursorMut`]: chain/struct.CursorMut.html " "
+    #[track_caller]
+    pub fn cursor_mut(&mut self, index: usize) -> CursorMut<'_, T, S, I, P> {
+        CursorMut::new(self, index)
+    }
 
     /// Sets the limit (**in elements, not bytes**) to which a buffer will be used before an additional allocation will be performed.
     ///
-    /// The limit must be even, i.e. a multiple of 2, due to how the limit is stored internally. Check out the source code if you're curious.
+    /// The limit must be even, i.e. a multiple of 2, due to how the limit is stored internally. Check out the source code if you're curious. Storages with a fixed capacity, such as `ArrayVec`, are never filled past that capacity, even if the limit is higher.
     pub fn set_limit(&mut self, mut limit: usize) {
         limit &= UsizeAndFlag::SIZE_MASK;
         limit = if limit < 2 { 2 } else { limit };
-        self.limit.set_size(limit)
+        self.limit.set_size(limit);
     }
     /// Returns the currently set limit to which a buffer will be used before an additional allocation will be performed.
     pub fn limit(&self) -> usize {
         self.limit.size()
     }
     /// Sets whether additional buffers will be allocated to the limit right away or will first allocate as much as needed and only then rellocate to the limit. Enabled by default.
+    ///
+    /// Storages which are split off a full one are allocated to fit the elements moved into them either way, so that the elements are moved only once.
     pub fn allocate_to_limit(&mut self, allocate_to_limit: bool) {
-        self.limit.set_flag(allocate_to_limit)
+        self.limit.set_flag(allocate_to_limit);
     }
     /// Returns whether [`allocate_to_limit`] is enabled.
     ///
@@ -81,12 +241,227 @@ where
     pub fn allocates_to_limit(&self) -> bool {
         self.limit.flag()
     }
+    /// Sets whether a storage which is filled up to the limit is split in two when an element is inserted into it, instead of growing past the limit. Enabled by default.
+    ///
+    /// Disabling this makes insertions into full storages cheaper, but the storages can then grow unboundedly, reintroducing the reallocation lag spikes the chain exists to prevent.
+    pub fn set_split_on_insert(&mut self, split_on_insert: bool) {
+        self.split_on_insert = split_on_insert;
+    }
+    /// Returns whether [`set_split_on_insert`] is enabled.
+    ///
+    /// [`set_split_on_insert`]: #method.set_split_on_insert " "
+    pub const fn splits_on_insert(&self) -> bool {
+        self.split_on_insert
+    }
+    /// Sets the amount of elements below which a storage is merged with one of its neighbors after an element is removed from it, provided that both fit into the limit together. Setting it to zero disables merging.
+    ///
+    /// If this is never called, the low-water mark is a quarter of the limit, and follows the limit when it's changed.
+    pub fn set_low_water_mark(&mut self, low_water_mark: usize) {
+        self.low_water_mark = Some(low_water_mark);
+    }
+    /// Returns the current [low-water mark].
+    ///
+    /// [low-water mark]: #method.set_low_water_mark " "
+    pub fn low_water_mark(&self) -> usize {
+        self.low_water_mark.unwrap_or_else(|| self.limit() / 4)
+    }
+    /// Splits the chain into two at the specified index, returning a new chain with the elements starting from that index and leaving the ones before it in `self`. The new chain has the same settings as this one.
+    ///
+    /// Only the storage which contains the element at the index has its elements moved: the storages after it are moved over as a whole. This makes the operation take *O(n + limit)* time, where *n* is the number of storages, regardless of the number of elements.
+    ///
+    /// # Panics
+    /// Panics if `at > len()`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, Chain};
+    ///
+    /// let mut text = Chain::<char, Vec<char>, Vec<Vec<char>>, Vec<usize>>::new();
+    /// text.set_limit(4);
+    /// "Hello, world!".chars().for_each(|c| text.push(c));
+    ///
+    /// let mut tail = text.split_off(5);
+    /// assert_eq!(text.elements().collect::<String>(), "Hello");
+    /// assert_eq!(tail.elements().collect::<String>(), ", world!");
+    ///
+    /// let mut name = Chain::new();
+    /// "Granite".chars().for_each(|c| name.push(c));
+    /// let removed = tail.splice_chain(2..7, name);
+    /// assert_eq!(removed.elements().collect::<String>(), "world");
+    ///
+    /// text.append(&mut tail);
+    /// assert!(tail.is_empty());
+    /// assert_eq!(text.elements().collect::<String>(), "Hello, Granite!");
+    /// ```
+    #[track_caller]
+    #[must_use = "use `truncate` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len, "index out of bounds");
+        let mut tail = self.empty_like();
+        let (mut storage, offset) = match self.locate(at) {
+            Some(x) => x,
+            None => return tail,
+        };
+        if offset != 0 {
+            self.split_storage_at(storage, offset);
+            storage += 1;
+        }
+        self.move_storages_into(storage, &mut tail.contents);
+        tail.len = self.len - at;
+        self.len = at;
+        // The storages which remain all come before the ones which were moved out
+        self.rebuild_lengths_from(self.contents.len());
+        tail.rebuild_lengths_from(0);
+        tail
+    }
+    /// Moves all elements of `other` to the end of this chain, leaving `other` empty.
+    ///
+    /// The storages of `other` are moved over as a whole, which makes the operation take *O(n + limit)* time, where *n* is the number of storages in both chains, regardless of the number of elements. The storages around the boundary are merged if they are below the [low-water mark]. Since the storages are not resized, they can be bigger than the limit of this chain if `other` has a higher one.
+    ///
+    /// [low-water mark]: #method.set_low_water_mark " "
+    pub fn append(&mut self, other: &mut Self) {
+        // Leaving the reserved storages in place would put empty storages in the middle
+        self.pop_empty_storages();
+        let boundary = self.contents.len();
+        other.move_storages_into(0, &mut self.contents);
+        self.len += other.len;
+        other.len = 0;
+        self.rebuild_lengths_from(boundary);
+        other.rebuild_lengths_from(0);
+        if boundary < self.contents.len() {
+            self.merge_if_below_low_water_mark(boundary);
+            if boundary > 0 {
+                self.merge_if_below_low_water_mark(boundary - 1);
+            }
+        }
+    }
+    /// Replaces the specified range of elements with the elements of another chain, returning the removed elements as a chain with the same settings as this one.
+    ///
+    /// This is equivalent to two calls to [`split_off`] followed by two calls to [`append`], and takes *O(n + limit)* time just like they do.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds or its start is greater than its end.
+    ///
+    /// [`split_off`]: #method.split_off " "
+    /// [`append`]: #method.append " "
+    #[track_caller]
+    #[allow(clippy::return_self_not_must_use)] // Discarding the removed elements is fine
+    pub fn splice_chain(&mut self, range: impl RangeBounds<usize>, mut other: Self) -> Self {
+        let (start, end) = self.resolve_range(range);
+        let mut tail = self.split_off(end);
+        let removed = self.split_off(start);
+        self.append(&mut other);
+        self.append(&mut tail);
+        removed
+    }
+    /// Same as [`splice_chain`], but notifies the elements after the range of their shift using [`MoveFix`].
+    ///
+    /// All of those elements are shifted by the same amount, so the shift is reported with a single call to `MoveFix::fix_range_shift`, which the element type can override to fix its indices in a single pass over the chain. Like with `insert_and_shiftfix` and `remove_and_shiftfix`, the inserted and removed elements themselves are not notified.
+    ///
+    /// # Panics
+    /// Same as `splice_chain`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use core::{num::NonZeroIsize, ops::Range};
+    /// use granite::{ListStorage, MoveFix, Chain};
+    ///
+    /// // A node which points to another node by its index
+    /// struct Node(usize);
+    /// impl MoveFix for Node {
+    ///     unsafe fn fix_shift<S>(_: &mut S, _: usize, _: NonZeroIsize)
+    ///     where
+    ///         S: ListStorage<Element = Self>,
+    ///     {
+    ///         unreachable!()
+    ///     }
+    ///     unsafe fn fix_move<S>(_: &mut S, _: usize, _: usize)
+    ///     where
+    ///         S: ListStorage<Element = Self>,
+    ///     {
+    ///         unreachable!("the range shift is reported as a whole")
+    ///     }
+    ///     unsafe fn fix_range_shift<S>(storage: &mut S, range: Range<usize>, shifted_by: NonZeroIsize)
+    ///     where
+    ///         S: ListStorage<Element = Self>,
+    ///     {
+    ///         for index in 0..storage.len() {
+    ///             let node = storage.get_mut(index).unwrap();
+    ///             if range.contains(&node.0) {
+    ///                 node.0 = (node.0 as isize + shifted_by.get()) as usize;
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut chain = Chain::<Node, Vec<Node>, Vec<Vec<Node>>, Vec<usize>>::new();
+    /// chain.set_limit(4);
+    /// for _ in 0..8 {
+    ///     chain.push(Node(7));
+    /// }
+    /// // The inserted node points to the first node, which doesn't get shifted
+    /// let mut inserted = Chain::new();
+    /// inserted.push(Node(0));
+    /// let removed = chain.splice_chain_and_shiftfix(1..3, inserted);
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(
+    ///     chain.elements().map(|node| node.0).collect::<Vec<_>>(),
+    ///     [6, 0, 6, 6, 6, 6, 6],
+    /// );
+    /// ```
+    ///
+    /// [`splice_chain`]: #method.splice_chain " "
+    /// [`MoveFix`]: trait.MoveFix.html " "
+    #[track_caller]
+    #[allow(clippy::return_self_not_must_use)] // Same as above
+    #[allow(clippy::cast_possible_wrap)] // Lengths never exceed isize::MAX
+    pub fn splice_chain_and_shiftfix(&mut self, range: impl RangeBounds<usize>, other: Self) -> Self
+    where
+        T: MoveFix,
+    {
+        let (start, end) = self.resolve_range(range);
+        let old_len = self.len;
+        let shifted_by = other.len as isize - (end - start) as isize;
+        let removed = self.splice_chain(start..end, other);
+        if let Some(shifted_by) = NonZeroIsize::new(shifted_by) {
+            if end < old_len {
+                unsafe {
+                    // SAFETY: the elements after the range were all shifted by the difference in length
+                    T::fix_range_shift(self, end..old_len, shifted_by);
+                }
+            }
+        }
+        removed
+    }
+    /// Converts range bounds into the start and end of the range, checking them against the length of the chain.
+    #[track_caller]
+    fn resolve_range(&self, range: impl RangeBounds<usize>) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(&x) => x,
+            Bound::Excluded(&x) => x + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&x) => x + 1,
+            Bound::Excluded(&x) => x,
+            Bound::Unbounded => self.len,
+        };
+        assert!(start <= end, "range start is greater than range end");
+        assert!(end <= self.len, "range out of bounds");
+        (start, end)
+    }
     /// Returns the number of separate storages used.
     pub fn num_storages(&self) -> usize {
         self.contents.len()
     }
+    /// Returns the limit, lowered to the capacity of the storages if they have a fixed one.
+    fn storage_limit(&self) -> usize {
+        let limit = self.limit();
+        S::CAPACITY.map_or(limit, |capacity| limit.min(capacity))
+    }
     fn push_allocated_storage(&mut self) -> &mut S {
-        self.contents.push(S::with_capacity(self.limit()));
+        self.contents.push(S::with_capacity(self.storage_limit()));
+        self.lengths.push(0);
         unsafe {
             // SAFETY: we just pushed that; see contract on ListStorage
             self.contents
@@ -95,6 +470,7 @@ where
     }
     fn push_empty_storage(&mut self) -> &mut S {
         self.contents.push(S::new());
+        self.lengths.push(0);
         unsafe {
             // SAFETY: as above
             self.contents
@@ -108,18 +484,273 @@ where
             self.push_empty_storage()
         }
     }
-    fn last_mut(&mut self) -> Option<&mut S> {
-        self.contents
-            .len()
+    /// Appends an empty storage, failing if the index storages are full.
+    fn try_push_storage(&mut self) -> Result<(), CapacityError> {
+        let storage = if self.allocates_to_limit() {
+            S::with_capacity(self.storage_limit())
+        } else {
+            S::new()
+        };
+        self.lengths.try_push(0)?;
+        if let Err(e) = self.contents.try_push(storage) {
+            self.lengths.pop();
+            return Err(e.simplify());
+        }
+        Ok(())
+    }
+    /// Appends empty storages with the specified capacities, for which room has already been reserved in the index storages. If allocating one of them fails, the ones appended before it are popped again, which leaves the chain as it was.
+    fn append_reserved_storages(
+        &mut self,
+        capacities: impl Iterator<Item = usize>,
+    ) -> Result<(), CapacityError> {
+        let old_num_storages = self.contents.len();
+        for capacity in capacities {
+            let mut st = S::new();
+            if let Err(e) = st.try_reserve(capacity) {
+                while self.contents.len() > old_num_storages {
+                    self.pop_storage();
+                }
+                return Err(e);
+            }
+            // Can't fail, since room for the new storages was reserved beforehand
+            self.contents.push(st);
+            self.lengths.push(0);
+        }
+        Ok(())
+    }
+    /// Removes the last storage, updating the length of the chain and the index of storage lengths.
+    fn pop_storage(&mut self) -> Option<S> {
+        let st = self.contents.pop()?;
+        self.lengths.pop();
+        self.len -= st.len();
+        Some(st)
+    }
+    /// Calls the specified closure on the storage at the specified index, then updates the length of the chain and the index of storage lengths with the change in its length.
+    ///
+    /// # Panics
+    /// Panics if there is no storage at the specified index.
+    fn modify_storage<R>(&mut self, index: usize, f: impl FnOnce(&mut S) -> R) -> R {
+        let st = self
+            .contents
+            .get_mut(index)
+            .expect("storage index out of bounds");
+        let old_len = st.len();
+        let result = f(st);
+        let new_len = st.len();
+        self.lengths.update(index, old_len, new_len);
+        self.len = self.len - old_len + new_len;
+        debug_assert_eq!(
+            self.lengths.prefix(index + 1) - self.lengths.prefix(index),
+            new_len,
+            "index of storage lengths is out of sync",
+        );
+        self.debug_check_len();
+        result
+    }
+    /// Checks that the cached length of the chain is equal to the sum of the lengths of its storages as recorded by the index of storage lengths, in debug builds only.
+    fn debug_check_len(&self) {
+        debug_assert_eq!(
+            self.lengths.len(),
+            self.contents.len(),
+            "index of storage lengths has the wrong number of storages",
+        );
+        debug_assert_eq!(
+            self.lengths.prefix(self.lengths.len()),
+            self.len,
+            "cached length of the chain is out of sync",
+        );
+    }
+    /// Returns the length of the storage at the specified index.
+    fn storage_len(&self, index: usize) -> usize {
+        self.contents.get(index).map_or(0, S::len)
+    }
+    /// Returns `true` if another storage can be added to the chain without exceeding the capacity of the index storages.
+    fn has_room_for_storage(&self) -> bool {
+        let len = self.contents.len();
+        I::CAPACITY.map_or(true, |capacity| len < capacity)
+            && P::CAPACITY.map_or(true, |capacity| len < capacity)
+    }
+    /// Rebuilds the index of storage lengths from the storages themselves, starting from the storage at the specified index, which also makes the debug check compare the cached length against the actual sum of their lengths.
+    ///
+    /// The nodes of the storages before the index never cover the ones after it, so only the storages from the index onwards are pushed anew, which takes *O(n - index)* time.
+    fn rebuild_lengths_from(&mut self, index: usize) {
+        self.lengths.truncate(index);
+        for index in index..self.contents.len() {
+            let len = self.storage_len(index);
+            self.lengths.push(len);
+        }
+        self.debug_check_len();
+    }
+    /// Returns the index of the storage which contains the last element, or 0 if the chain is empty. All storages after it are empty.
+    fn last_occupied_storage(&self) -> usize {
+        let num_storages = self.contents.len();
+        // Usually the last storage is the one, which spares the search
+        if num_storages != 0 && self.storage_len(num_storages - 1) != 0 {
+            return num_storages - 1;
+        }
+        self.len
             .checked_sub(1)
-            .and_then(move |i| self.contents.get_mut(i))
+            .and_then(|last| self.locate(last))
+            .map_or(0, |(storage, _)| storage)
+    }
+    /// Returns the index of the storage to which an element pushed to the end of the chain should go, or `None` if a new storage has to be created for it. Empty storages left over from `reserve` or `truncate` are filled before creating new ones.
+    fn push_target(&self) -> Option<usize> {
+        let storage = self.last_occupied_storage();
+        if storage >= self.contents.len() {
+            None
+        } else if self.storage_len(storage) < self.storage_limit() {
+            Some(storage)
+        } else if storage + 1 < self.contents.len() {
+            Some(storage + 1)
+        } else {
+            None
+        }
+    }
+    /// Returns the amount of elements which can be pushed before a new storage has to be created.
+    fn room_before_new_storage(&self) -> usize {
+        let limit = self.storage_limit();
+        let mut room = 0;
+        for index in self.last_occupied_storage()..self.contents.len() {
+            room += limit.saturating_sub(self.storage_len(index));
+        }
+        room
+    }
+    /// Pops the empty storages at the end, which exist as reserved capacity.
+    fn pop_empty_storages(&mut self) {
+        let keep = if self.len == 0 {
+            0
+        } else {
+            self.last_occupied_storage() + 1
+        };
+        while self.contents.len() > keep {
+            self.pop_storage();
+        }
+    }
+    /// Like `locate_insertion`, but splits the storage beforehand if it's already filled up to the limit and splitting is enabled.
+    #[track_caller]
+    fn prepare_insertion(&mut self, index: usize) -> (usize, usize) {
+        let (storage, offset) = self.locate_insertion(index);
+        if !self.needs_split(storage) {
+            return (storage, offset);
+        }
+        if index == self.len {
+            // Appending is better served by starting a new storage than by splitting the last one
+            self.push_storage();
+            return (self.contents.len() - 1, 0);
+        }
+        let mid = self.split_storage(storage);
+        if offset <= mid {
+            (storage, offset)
+        } else {
+            (storage + 1, offset - mid)
+        }
+    }
+    /// Returns `true` if inserting into the storage at the specified index would split it first.
+    fn needs_split(&self, index: usize) -> bool {
+        self.split_on_insert
+            && self.storage_len(index) >= self.storage_limit()
+            && self.has_room_for_storage()
+    }
+    /// Moves the second half of the storage at the specified index into a new storage right after it, returning the length of the first half.
+    fn split_storage(&mut self, index: usize) -> usize {
+        let len = self.storage_len(index);
+        let mid = len - len / 2;
+        self.split_storage_at(index, mid);
+        mid
+    }
+    /// Moves the elements starting from `at` in the storage at the specified index into a new storage right after it.
+    fn split_storage_at(&mut self, index: usize, at: usize) {
+        let st = unsafe {
+            // SAFETY: callers only pass indices of existing storages
+            self.contents.get_unchecked_mut(index)
+        };
+        let second_half = st.split_off(at);
+        self.contents.insert(index + 1, second_half);
+        self.rebuild_lengths_from(index);
+    }
+    /// Creates an empty chain with the same settings and no storages.
+    fn empty_like(&self) -> Self {
+        Self {
+            contents: I::new(),
+            lengths: Fenwick::new(),
+            len: 0,
+            limit: self.limit,
+            low_water_mark: self.low_water_mark,
+            split_on_insert: self.split_on_insert,
+        }
+    }
+    /// Moves the storages starting from the specified index to the end of another list of storages, keeping their order.
+    fn move_storages_into(&mut self, from: usize, destination: &mut I) {
+        if from == 0 {
+            destination.append(&mut self.contents);
+        } else {
+            destination.append(&mut self.contents.split_off(from));
+        }
+    }
+    /// Merges the storage at the specified index with one of its neighbors if it's below the low-water mark and they fit into the limit together.
+    fn merge_if_below_low_water_mark(&mut self, index: usize) {
+        let len = self.storage_len(index);
+        if len >= self.low_water_mark() || self.contents.len() < 2 {
+            return;
+        }
+        if len == 0 {
+            // An empty storage with no elements after it is kept as reserved capacity, so that
+            // popping and pushing around a storage boundary doesn't allocate every time
+            if self.lengths.prefix(index) != self.len {
+                self.contents.remove(index);
+                self.rebuild_lengths_from(index);
+            }
+            return;
+        }
+        let limit = self.storage_limit();
+        // Empty neighbors are reserved capacity at the end of the chain, merging into them is pointless
+        let fits = |other| {
+            other < self.contents.len()
+                && self.storage_len(other) != 0
+                && len + self.storage_len(other) <= limit
+        };
+        if fits(index + 1) {
+            self.merge_storages(index);
+        } else if index > 0 && fits(index - 1) {
+            self.merge_storages(index - 1);
+        }
+    }
+    /// Appends the elements of the storage after the one at the specified index to it and removes the then empty storage.
+    fn merge_storages(&mut self, index: usize) {
+        let mut second = self.contents.remove(index + 1);
+        let first = unsafe {
+            // SAFETY: callers only pass indices of existing storages
+            self.contents.get_unchecked_mut(index)
+        };
+        first.append(&mut second);
+        self.rebuild_lengths_from(index);
+    }
+    /// Returns the index of the storage which contains the element at the specified index and the index of the element inside that storage, or `None` if the index is out of bounds.
+    fn locate(&self, index: usize) -> Option<(usize, usize)> {
+        self.lengths.find(index)
+    }
+    /// Returns the index of the storage into which an element should go to end up at the specified index, and the index inside that storage. The end of the chain is located where `push` would put an element, or at the end of the last occupied storage if that one is full, and a storage is created if there are none.
+    #[track_caller]
+    fn locate_insertion(&mut self, index: usize) -> (usize, usize) {
+        if let Some(location) = self.locate(index) {
+            return location;
+        }
+        assert!(index == self.len, "index out of bounds");
+        if self.contents.is_empty() {
+            self.push_storage();
+        }
+        let storage = self
+            .push_target()
+            .unwrap_or_else(|| self.last_occupied_storage());
+        (storage, self.storage_len(storage))
     }
 }
 
-unsafe impl<T, S, I> ListStorage for Chain<T, S, I>
+unsafe impl<T, S, I, P> ListStorage for Chain<T, S, I, P>
 where
     S: List<Element = T>,
     I: List<Element = S>,
+    P: ListStorage<Element = usize>,
 {
     type Element = T;
     const CAPACITY: Option<usize> = {
@@ -129,112 +760,93 @@ where
             None
         }
     };
+    /// Uses `reserve` under the hood, so the capacity is split between storages no bigger than the limit.
     fn with_capacity(capacity: usize) -> Self {
-        let mut contents = I::new();
-        contents.push(S::with_capacity(capacity));
-        Self {
-            contents,
-            len: 0,
-            limit: UsizeAndFlag::new(Self::DEFAULT_LIMIT, Self::DEFAULT_ALLOCATE_TO_LIMIT),
-        }
+        let mut chain = Self::new();
+        chain.reserve(capacity);
+        chain
     }
     #[track_caller]
-    fn insert(&mut self, mut index: usize, element: Self::Element) {
-        for st in self.contents.iter_mut() {
-            let (new_index, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                st.insert(index, element);
-                self.len += 1;
-                return;
-            }
-            index = new_index;
-        }
-        panic!("index out of bounds")
+    fn insert(&mut self, index: usize, element: Self::Element) {
+        let (storage, index) = self.prepare_insertion(index);
+        self.modify_storage(storage, |st| st.insert(index, element));
     }
     #[track_caller]
-    fn remove(&mut self, mut index: usize) -> Self::Element {
-        for st in self.contents.iter_mut() {
-            let (new_index, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                self.len -= 1;
-                return st.remove(index);
-            }
-            index = new_index;
-        }
-        panic!("index out of bounds")
+    fn remove(&mut self, index: usize) -> Self::Element {
+        let (storage, index) = self.locate(index).expect("index out of bounds");
+        let element = self.modify_storage(storage, |st| st.remove(index));
+        self.merge_if_below_low_water_mark(storage);
+        element
     }
     fn len(&self) -> usize {
         self.len
     }
-    unsafe fn get_unchecked(&self, mut index: usize) -> &Self::Element {
-        for st in self.contents.iter() {
-            let (new_index, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                return st.get_unchecked(index);
-            }
-            index = new_index;
-        }
-        hint::unreachable_unchecked()
+    unsafe fn get_unchecked(&self, index: usize) -> &Self::Element {
+        let (storage, index) = self
+            .locate(index)
+            .unwrap_or_else(|| hint::unreachable_unchecked());
+        self.contents.get_unchecked(storage).get_unchecked(index)
     }
-    unsafe fn get_unchecked_mut(&mut self, mut index: usize) -> &mut Self::Element {
-        for st in self.contents.iter_mut() {
-            let (new_index, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                return st.get_unchecked_mut(index);
-            }
-            index = new_index;
-        }
-        hint::unreachable_unchecked()
+    unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut Self::Element {
+        let (storage, index) = self
+            .locate(index)
+            .unwrap_or_else(|| hint::unreachable_unchecked());
+        self.contents
+            .get_unchecked_mut(storage)
+            .get_unchecked_mut(index)
     }
 
-    fn get(&self, mut index: usize) -> Option<&Self::Element> {
-        for st in self.contents.iter() {
-            let (new_index, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                return st.get(index);
-            }
-            index = new_index;
+    fn get(&self, index: usize) -> Option<&Self::Element> {
+        let (storage, index) = self.locate(index)?;
+        unsafe {
+            // SAFETY: the Fenwick tree only returns indices of existing storages
+            self.contents.get_unchecked(storage)
         }
-        None
+        .get(index)
     }
-    fn get_mut(&mut self, mut index: usize) -> Option<&mut Self::Element> {
-        for st in self.contents.iter_mut() {
-            let (new_index, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                return st.get_mut(index);
-            }
-            index = new_index;
+    fn get_mut(&mut self, index: usize) -> Option<&mut Self::Element> {
+        let (storage, index) = self.locate(index)?;
+        unsafe {
+            // SAFETY: as above
+            self.contents.get_unchecked_mut(storage)
         }
-        None
+        .get_mut(index)
+    }
+    fn contains_index(&self, index: usize) -> bool {
+        self.locate(index).map_or(false, |(storage, index)| {
+            unsafe {
+                // SAFETY: as above
+                self.contents.get_unchecked(storage)
+            }
+            .contains_index(index)
+        })
     }
     fn new() -> Self {
         Self {
             contents: I::new(),
+            lengths: Fenwick::new(),
             len: 0,
             limit: UsizeAndFlag::new(Self::DEFAULT_LIMIT, Self::DEFAULT_ALLOCATE_TO_LIMIT),
+            low_water_mark: None,
+            split_on_insert: true,
         }
     }
     fn push(&mut self, element: Self::Element) {
-        let limit = self.limit();
-        let storage = self
-            .last_mut()
-            .and_then(|last| if last.len() < limit { Some(last) } else { None });
-        #[allow(clippy::option_if_let_else)] // Epic borrow checker fail
-        if let Some(st) = storage {
-            st.push(element);
+        let storage = if let Some(x) = self.push_target() {
+            x
         } else {
-            self.push_storage().push(element);
-        }
-        self.len += 1;
+            self.push_storage();
+            self.contents.len() - 1
+        };
+        self.modify_storage(storage, |st| st.push(element));
     }
+    /// A storage which is emptied by popping is kept as reserved capacity, like with `truncate`, so popping and pushing back and forth around a storage boundary doesn't create a new storage every time.
     fn pop(&mut self) -> Option<Self::Element> {
-        #[allow(clippy::option_if_let_else)] // Same here
-        if let Some(val) = self.last_mut().and_then(|storage| storage.pop()) {
-            self.len -= 1;
-            Some(val)
-        } else {
-            None
-        }
+        // The last storage can be empty reserved capacity, so look up the one with the last element
+        let (storage, _) = self.locate(self.len.checked_sub(1)?)?;
+        let element = self.modify_storage(storage, S::pop);
+        self.merge_if_below_low_water_mark(storage);
+        element
     }
     fn capacity(&self) -> usize {
         let mut capacity = 0;
@@ -243,75 +855,331 @@ where
         }
         capacity
     }
+    /// Reserves room in the storage with the last element and the empty storages after it before creating new ones, only creating as many as needed to fit the rest.
     fn reserve(&mut self, additional: usize) {
-        let limit = self.limit();
-        let num_full_storages = additional / limit;
-        let reserve_in_last_storage = additional % limit;
-        for _ in 0..num_full_storages {
+        let limit = self.storage_limit();
+        let mut remaining = additional;
+        for index in self.last_occupied_storage()..self.contents.len() {
+            if remaining == 0 {
+                return;
+            }
+            let st = unsafe {
+                // SAFETY: the index is in bounds as per the loop range
+                self.contents.get_unchecked_mut(index)
+            };
+            let room = limit.saturating_sub(st.len()).min(remaining);
+            st.reserve(room);
+            remaining -= room;
+        }
+        while remaining >= limit {
             self.push_allocated_storage();
+            remaining -= limit;
+        }
+        if remaining != 0 {
+            self.contents.push(S::with_capacity(remaining));
+            self.lengths.push(0);
         }
-        self.contents
-            .push(S::with_capacity(reserve_in_last_storage));
     }
+    /// Removes empty storages, including the ones left over from `reserve`, `truncate` and removals at the end, and shrinks the rest.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, Chain};
+    ///
+    /// let mut chain = Chain::<u32, Vec<u32>, Vec<Vec<u32>>, Vec<usize>>::new();
+    /// chain.set_limit(4);
+    /// chain.reserve(12);
+    /// assert_eq!(chain.num_storages(), 3);
+    /// for value in 0..6 {
+    ///     chain.push(value);
+    /// }
+    /// // The reserved storages were filled instead of creating new ones
+    /// assert_eq!(chain.num_storages(), 3);
+    /// chain.shrink_to_fit();
+    /// assert_eq!(chain.num_storages(), 2);
+    /// assert_eq!(chain.len(), 6);
+    /// assert_eq!(chain.capacity(), 6);
+    /// ```
     fn shrink_to_fit(&mut self) {
-        let mut i = 0;
-        let mut j = self.contents.len();
-        while i < j {
+        // Move the non-empty storages down over the empty ones in a single pass, leaving the
+        // empty ones at the end to be truncated off
+        let mut kept = 0;
+        for index in 0..self.contents.len() {
             let st = unsafe {
                 // SAFETY: see contract of ListStorage
-                self.contents.get_unchecked_mut(i)
+                self.contents.get_unchecked_mut(index)
             };
             if st.len() == 0 {
-                self.remove(0);
-                j -= 1;
-            } else {
-                st.shrink_to_fit();
-                i += 1;
+                continue;
+            }
+            st.shrink_to_fit();
+            if index != kept {
+                let st = mem::replace(st, S::new());
+                *unsafe {
+                    // SAFETY: kept < index, which is in bounds
+                    self.contents.get_unchecked_mut(kept)
+                } = st;
             }
+            kept += 1;
         }
+        self.contents.truncate(kept);
+        self.contents.shrink_to_fit();
+        self.rebuild_lengths_from(0);
+        self.lengths.shrink_to_fit();
     }
-    fn truncate(&mut self, mut len: usize) {
-        for st in self.contents.iter_mut() {
-            let (new_len, is_end) = len.overflowing_sub(st.len());
-            if is_end {
-                st.truncate(len);
-                return;
-            }
-            len = new_len;
+    /// The storages which end up empty are kept as reserved capacity, just like `Vec` keeps its buffer. Use `shrink_to_fit` to free them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, Chain};
+    ///
+    /// let mut chain = Chain::<u32, Vec<u32>, Vec<Vec<u32>>, Vec<usize>>::new();
+    /// chain.set_limit(4);
+    /// for value in 0..10 {
+    ///     chain.push(value);
+    /// }
+    /// chain.truncate(5);
+    /// assert_eq!(chain.len(), 5);
+    /// assert_eq!(chain.elements().count(), 5);
+    /// assert_eq!(chain.get(5), None);
+    /// assert_eq!(chain.num_storages(), 3);
+    /// chain.push(100);
+    /// assert_eq!(chain.get(5), Some(&100));
+    /// assert_eq!(chain.pop(), Some(100));
+    /// assert_eq!(chain.pop(), Some(4));
+    /// chain.truncate(0);
+    /// assert!(chain.is_empty());
+    /// assert_eq!(chain.pop(), None);
+    /// ```
+    fn truncate(&mut self, len: usize) {
+        let (storage, offset) = match self.locate(len) {
+            Some(x) => x,
+            None => return,
+        };
+        for index in storage..self.contents.len() {
+            let st = unsafe {
+                // SAFETY: the index is in bounds as per the loop range
+                self.contents.get_unchecked_mut(index)
+            };
+            st.truncate(if index == storage { offset } else { 0 });
         }
+        self.len = len;
+        self.rebuild_lengths_from(storage);
     }
     #[track_caller]
-    fn insert_and_shiftfix(&mut self, mut index: usize, element: Self::Element)
+    fn split_off(&mut self, at: usize) -> Self {
+        self.split_off(at)
+    }
+    fn append(&mut self, other: &mut Self) {
+        self.append(other);
+    }
+    /// Inserts the element into the storage which should contain it, without notifying anything there, and then calls `MoveFix` with the chain itself as the storage and the index in the whole chain, so that elements which store indices into the chain get them fixed correctly. Splitting and merging storages never changes the index of an element in the whole chain, so it doesn't produce any notifications.
+    ///
+    /// # Example
+    /// ```rust
+    /// use core::num::NonZeroIsize;
+    /// use granite::{ListStorage, Chain, MoveFix};
+    ///
+    /// /// Points to another element of the same list by its index.
+    /// struct Link(Option<usize>);
+    /// impl MoveFix for Link {
+    ///     unsafe fn fix_shift<S>(storage: &mut S, shifted_from: usize, shifted_by: NonZeroIsize)
+    ///     where
+    ///         S: ListStorage<Element = Self>,
+    ///     {
+    ///         for index in 0..storage.len() {
+    ///             if let Some(target) = &mut storage.get_mut(index).unwrap().0 {
+    ///                 if *target >= shifted_from {
+    ///                     *target = (*target as isize + shifted_by.get()) as usize;
+    ///                 }
+    ///             }
+    ///         }
+    ///     }
+    ///     unsafe fn fix_move<S>(_: &mut S, _: usize, _: usize)
+    ///     where
+    ///         S: ListStorage<Element = Self>,
+    ///     {
+    ///     }
+    /// }
+    ///
+    /// let mut chain = Chain::<Link, Vec<Link>, Vec<Vec<Link>>, Vec<usize>>::new();
+    /// chain.set_limit(4);
+    /// // All elements after the first one link to it, across storage boundaries
+    /// chain.push(Link(None));
+    /// for _ in 0..10 {
+    ///     chain.push(Link(Some(0)));
+    /// }
+    /// for _ in 0..5 {
+    ///     chain.insert_and_shiftfix(0, Link(None));
+    /// }
+    /// assert_eq!(chain.len(), 16);
+    /// assert!(chain.elements().skip(6).all(|link| link.0 == Some(5)));
+    /// chain.remove_and_shiftfix(0);
+    /// chain.remove_and_shiftfix(0);
+    /// assert_eq!(chain.len(), 14);
+    /// assert!(chain.elements().skip(4).all(|link| link.0 == Some(3)));
+    /// ```
+    #[track_caller]
+    fn insert_and_shiftfix(&mut self, index: usize, element: Self::Element)
     where
         Self::Element: MoveFix,
     {
-        for st in self.contents.iter_mut() {
-            let (new_index, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                st.insert_and_shiftfix(index, element);
-                return;
-            }
-            index = new_index;
+        self.insert(index, element);
+        unsafe {
+            // SAFETY: the elements starting from the index were shifted in the whole chain
+            Self::Element::fix_right_shift(self, index, super::U_ONE);
         }
-        panic!("index out of bounds")
     }
+    /// Removes the element from the storage which contains it and then calls `MoveFix` with the chain itself as the storage, just like [`insert_and_shiftfix`].
+    ///
+    /// [`insert_and_shiftfix`]: #method.insert_and_shiftfix " "
     #[track_caller]
-    fn remove_and_shiftfix(&mut self, mut index: usize) -> Self::Element
+    fn remove_and_shiftfix(&mut self, index: usize) -> Self::Element
     where
         Self::Element: MoveFix,
     {
-        for st in self.contents.iter_mut() {
-            let (new_len, is_end) = index.overflowing_sub(st.len());
-            if is_end {
-                return st.remove_and_shiftfix(index);
-            }
-            index = new_len;
+        let element = self.remove(index);
+        unsafe {
+            // SAFETY: as above
+            Self::Element::fix_left_shift(self, index, super::U_ONE);
         }
-        panic!("index out of bounds")
+        element
     }
     /// Uses `push` under the hood. The `Chain` should be wrapped in `SparseStorage`, not vice versa.
     fn add(&mut self, element: Self::Element) -> usize {
         self.push(element);
         self.len - 1
     }
+    #[track_caller]
+    fn try_insert(
+        &mut self,
+        index: usize,
+        element: Self::Element,
+    ) -> Result<(), CapacityError<Self::Element>> {
+        let (storage, index) = self.prepare_insertion(index);
+        self.modify_storage(storage, |st| st.try_insert(index, element))
+    }
+    fn try_push(&mut self, element: Self::Element) -> Result<(), CapacityError<Self::Element>> {
+        let storage = self.push_target();
+        // If the last storage is below the limit but still cannot fit the element,
+        // try again with a new storage before giving up.
+        let element = if let Some(storage) = storage {
+            match self.modify_storage(storage, |st| st.try_push(element)) {
+                Ok(()) => return Ok(()),
+                Err(e) => e.element(),
+            }
+        } else {
+            element
+        };
+        if self.try_push_storage().is_err() {
+            return Err(CapacityError::new(element));
+        }
+        let last = self.contents.len() - 1;
+        self.modify_storage(last, |st| st.try_push(element))
+    }
+    /// Uses `try_push` under the hood.
+    fn try_add(&mut self, element: Self::Element) -> Result<usize, CapacityError<Self::Element>> {
+        self.try_push(element)?;
+        Ok(self.len - 1)
+    }
+    /// Reserves room in the same storages as `reserve`, but checks that the index storages can fit the new storages before creating any of them.
+    ///
+    /// If an error is returned, the chain has the same elements and storages as before the call. Storages which gained capacity before the error, including the index storages, keep it.
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        let limit = self.storage_limit();
+        let missing = additional.saturating_sub(self.room_before_new_storage());
+        let num_storages = missing / limit + usize::from(missing % limit != 0);
+        self.contents.try_reserve(num_storages)?;
+        self.lengths.try_reserve(num_storages)?;
+        let mut remaining = additional;
+        for index in self.last_occupied_storage()..self.contents.len() {
+            if remaining == 0 {
+                break;
+            }
+            let st = unsafe {
+                // SAFETY: the index is in bounds as per the loop range
+                self.contents.get_unchecked_mut(index)
+            };
+            let room = limit.saturating_sub(st.len()).min(remaining);
+            st.try_reserve(room)?;
+            remaining -= room;
+        }
+        let full = repeat(limit).take(remaining / limit);
+        let rest = Some(remaining % limit).filter(|&rest| rest != 0);
+        self.append_reserved_storages(full.chain(rest))
+    }
+}
+impl<'a, T, S, I, P> IntoRefIterator<'a> for Chain<T, S, I, P>
+where
+    T: 'a,
+    S: List<Element = T> + 'a,
+    I: List<Element = S>,
+    P: ListStorage<Element = usize>,
+{
+    type Item = T;
+    type Iter = Elements<'a, S, I>;
+    /// Returns an iterator over references to the elements, same as [`elements`].
+    ///
+    /// [`elements`]: struct.Chain.html#method.elements " "
+    fn iter(&'a self) -> Self::Iter {
+        self.elements()
+    }
+}
+impl<'a, T, S, I, P> IntoMutIterator<'a> for Chain<T, S, I, P>
+where
+    T: 'a,
+    S: List<Element = T> + 'a,
+    I: List<Element = S>,
+    P: ListStorage<Element = usize>,
+{
+    type Item = T;
+    type IterMut = ElementsMut<'a, S, I>;
+    /// Returns an iterator over mutable references to the elements, same as [`elements_mut`].
+    ///
+    /// [`elements_mut`]: struct.Chain.html#method.elements_mut " "
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        self.elements_mut()
+    }
+}
+// The storages have to be dense too, otherwise their holes would count towards the length
+unsafe impl<T, S, I, P> DenseList for Chain<T, S, I, P>
+where
+    S: List<Element = T> + DenseList,
+    I: List<Element = S>,
+    P: ListStorage<Element = usize>,
+{
+}
+#[cfg(feature = "rayon")]
+impl<'a, T, S, I, P> IntoParRefIterator<'a> for Chain<T, S, I, P>
+where
+    T: Sync + 'a,
+    S: List<Element = T> + Sync + 'a,
+    I: List<Element = S> + IntoParRefIterator<'a, Item = S>,
+    P: ListStorage<Element = usize>,
+{
+    type Item = T;
+    type ParIter = ParElements<'a, S, I>;
+    /// Returns a parallel iterator over references to the elements, same as [`par_elements`].
+    ///
+    /// [`par_elements`]: struct.Chain.html#method.par_elements " "
+    fn par_elements(&'a self) -> Self::ParIter {
+        Chain::par_elements(self)
+    }
+}
+#[cfg(feature = "rayon")]
+impl<'a, T, S, I, P> IntoParMutIterator<'a> for Chain<T, S, I, P>
+where
+    T: Send + 'a,
+    S: List<Element = T> + Send + 'a,
+    I: List<Element = S> + IntoParMutIterator<'a, Item = S>,
+    P: ListStorage<Element = usize>,
+{
+    type Item = T;
+    type ParIterMut = ParElementsMut<'a, S, I>;
+    /// Returns a parallel iterator over mutable references to the elements, same as [`par_elements_mut`].
+    ///
+    /// [`par_elements_mut`]: struct.Chain.html#method.par_elements_mut " "
+    fn par_elements_mut(&'a mut self) -> Self::ParIterMut {
+        Chain::par_elements_mut(self)
+    }
 }
@@ -0,0 +1,115 @@
+use core::fmt::{self, Debug, Formatter};
+use rayon::iter::{plumbing::UnindexedConsumer, FlatMapIter, ParallelIterator};
+use crate::{
+    List,
+    ListStorage,
+    IntoRefIterator,
+    IntoMutIterator,
+    IntoParRefIterator,
+    IntoParMutIterator,
+};
+
+type StorageIter<'a, S> = fn(&'a S) -> <S as IntoRefIterator<'a>>::Iter;
+type StorageIterMut<'a, S> = fn(&'a mut S) -> <S as IntoMutIterator<'a>>::IterMut;
+
+/// A parallel iterator over references to the elements of a [`Chain`], which distributes the storages between threads and walks each of them sequentially.
+///
+/// Created by the [`par_elements`] method.
+///
+/// [`Chain`]: struct.Chain.html " "
+/// [`par_elements`]: struct.Chain.html#method.par_elements " "
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+pub struct ParElements<'a, S, I>(
+    FlatMapIter<<I as IntoParRefIterator<'a>>::ParIter, StorageIter<'a, S>>,
+)
+where
+    S: List + Sync,
+    <S as ListStorage>::Element: Sync,
+    I: List<Element = S> + IntoParRefIterator<'a, Item = S>;
+impl<'a, S, I> ParElements<'a, S, I>
+where
+    S: List + Sync + 'a,
+    <S as ListStorage>::Element: Sync,
+    I: List<Element = S> + IntoParRefIterator<'a, Item = S>,
+{
+    pub(super) fn new(storages: <I as IntoParRefIterator<'a>>::ParIter) -> Self {
+        Self(storages.flat_map_iter(IntoRefIterator::iter))
+    }
+}
+impl<'a, S, I> ParallelIterator for ParElements<'a, S, I>
+where
+    S: List + Sync + 'a,
+    <S as ListStorage>::Element: Sync,
+    I: List<Element = S> + IntoParRefIterator<'a, Item = S>,
+{
+    type Item = &'a <S as ListStorage>::Element;
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.0.drive_unindexed(consumer)
+    }
+}
+impl<'a, S, I> Debug for ParElements<'a, S, I>
+where
+    S: List + Sync + 'a,
+    <S as ListStorage>::Element: Sync,
+    I: List<Element = S> + IntoParRefIterator<'a, Item = S>,
+    <I as IntoParRefIterator<'a>>::ParIter: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("chain::ParElements").field(&self.0).finish()
+    }
+}
+
+/// A parallel iterator over mutable references to the elements of a [`Chain`], which distributes the storages between threads and walks each of them sequentially.
+///
+/// Created by the [`par_elements_mut`] method.
+///
+/// [`Chain`]: struct.Chain.html " "
+/// [`par_elements_mut`]: struct.Chain.html#method.par_elements_mut " "
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+pub struct ParElementsMut<'a, S, I>(
+    FlatMapIter<<I as IntoParMutIterator<'a>>::ParIterMut, StorageIterMut<'a, S>>,
+)
+where
+    S: List + Send,
+    <S as ListStorage>::Element: Send,
+    I: List<Element = S> + IntoParMutIterator<'a, Item = S>;
+impl<'a, S, I> ParElementsMut<'a, S, I>
+where
+    S: List + Send + 'a,
+    <S as ListStorage>::Element: Send,
+    I: List<Element = S> + IntoParMutIterator<'a, Item = S>,
+{
+    pub(super) fn new(storages: <I as IntoParMutIterator<'a>>::ParIterMut) -> Self {
+        Self(storages.flat_map_iter(IntoMutIterator::iter_mut))
+    }
+}
+impl<'a, S, I> ParallelIterator for ParElementsMut<'a, S, I>
+where
+    S: List + Send + 'a,
+    <S as ListStorage>::Element: Send,
+    I: List<Element = S> + IntoParMutIterator<'a, Item = S>,
+{
+    type Item = &'a mut <S as ListStorage>::Element;
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.0.drive_unindexed(consumer)
+    }
+}
+impl<'a, S, I> Debug for ParElementsMut<'a, S, I>
+where
+    S: List + Send + 'a,
+    <S as ListStorage>::Element: Send,
+    I: List<Element = S> + IntoParMutIterator<'a, Item = S>,
+    <I as IntoParMutIterator<'a>>::ParIterMut: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("chain::ParElementsMut")
+            .field(&self.0)
+            .finish()
+    }
+}
@@ -0,0 +1,592 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::{repeat, Enumerate, FusedIterator, Map},
+    mem::size_of,
+};
+use crate::{
+    list::sparse::{Slot, Iter as SlotIter, IterMut as SlotIterMut, Indexed},
+    DenseList,
+    List,
+    ListStorage,
+    SparseStorage,
+    Storage,
+    StorageIter,
+    IntoRefIterator,
+    IntoMutIterator,
+    CapacityError,
+};
+use super::Chain;
+
+/// A [`SparseChain`] of `Vec`s.
+///
+/// [`SparseChain`]: struct.SparseChain.html " "
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "alloc")))]
+pub type Vec<T> = SparseChain<
+    T,
+    alloc::vec::Vec<Slot<T>>,
+    alloc::vec::Vec<usize>,
+    alloc::vec::Vec<crate::SparseVec<T>>,
+    alloc::vec::Vec<usize>,
+>;
+
+/// A storage wrapping a [`Chain`] of [`SparseStorage`]s, combining the lag spike prevention of `Chain` with the stable keys of sparse storage.
+///
+/// Like with `Chain`, a single storage never grows past the limit, and new storages are allocated instead, so growing never reallocates or moves existing elements. Unlike with `Chain`, storages are never split or merged, and removing an element punches a hole into its storage instead of shifting the elements after it. The key of an element is a [`SparseChainKey`] — the index of the storage and the index inside of it — which stays valid until the element is removed, regardless of how many elements are added or removed in the meantime.
+///
+/// This means that looking up an element by key takes *O(1)* time, without walking over the storages or consulting the index of their lengths. Newly added elements fill the holes left by removed ones before taking up room at the end of a storage, with storages which appear earlier in the chain being filled first.
+///
+/// The underlying chain is available through [`as_chain`], which counts holes towards the lengths of the storages. This makes it possible to convert between keys and positions in the chain in *O(log n)* time, where *n* is the number of storages, using [`position_of`] and [`key_at`].
+///
+/// Since elements are never moved, the elements don't need to implement `MoveFix`. This, however, also means that the holes are never defragmented: a storage which had lots of elements removed from it keeps its size until they are replaced with new ones.
+///
+/// # Example
+/// ```rust
+/// use granite::{Storage, SparseChainVec};
+///
+/// let mut storage = SparseChainVec::<u32>::new();
+/// storage.set_limit(4);
+/// let keys = (0..10).map(|value| storage.add(value)).collect::<Vec<_>>();
+/// assert_eq!(storage.num_storages(), 3);
+/// assert_eq!(keys[9].storage, 2);
+/// assert_eq!(keys[9].index, 1);
+///
+/// // Removing elements doesn't invalidate the keys of other elements...
+/// assert_eq!(storage.remove(&keys[1]), 1);
+/// assert_eq!(storage.remove(&keys[5]), 5);
+/// assert_eq!(storage.get(&keys[1]), None);
+/// assert_eq!(storage.get(&keys[6]), Some(&6));
+/// assert_eq!(storage.position_of(&keys[6]), Some(6));
+/// // ...and new elements fill the holes before growing the chain.
+/// let key = storage.add(100);
+/// assert_eq!(key, keys[1]);
+/// assert_eq!(storage.len(), 9);
+/// assert_eq!(storage.num_storages(), 3);
+/// assert_eq!(storage.get(&keys[9]), Some(&9));
+/// ```
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`Chain`]: struct.Chain.html " "
+/// [`SparseChainKey`]: struct.SparseChainKey.html " "
+/// [`as_chain`]: #method.as_chain " "
+/// [`position_of`]: #method.position_of " "
+/// [`key_at`]: #method.key_at " "
+#[derive(Clone, Debug)]
+pub struct SparseChain<E, S, B, I, P>
+where
+    S: List<Element = Slot<E>> + DenseList,
+    B: ListStorage<Element = usize>,
+    I: List<Element = SparseStorage<E, S, B>>,
+    P: ListStorage<Element = usize>,
+    SparseStorage<E, S, B>: List<Element = E>,
+{
+    /// The lengths of the storages in its index count the holes as well.
+    chain: Chain<E, SparseStorage<E, S, B>, I, P>,
+    /// The amount of elements, not counting holes.
+    len: usize,
+    /// All storages before this one are filled up to the limit and have no holes.
+    first_free: usize,
+}
+impl<E, S, B, I, P> SparseChain<E, S, B, I, P>
+where
+    S: List<Element = Slot<E>> + DenseList,
+    B: ListStorage<Element = usize>,
+    I: List<Element = SparseStorage<E, S, B>>,
+    P: ListStorage<Element = usize>,
+    SparseStorage<E, S, B>: List<Element = E>,
+{
+    const DEFAULT_LIMIT: usize = {
+        let base = 2048 / size_of::<Slot<E>>();
+        if base < 2 {
+            // Same as with Chain: UsizeAndFlag repurposes the lowest bit.
+            2
+        } else {
+            base
+        }
+    };
+
+    /// Sets the limit (**in slots, not bytes**) to which a buffer will be used before an additional allocation will be performed. Holes count towards the limit just like elements do.
+    ///
+    /// The limit must be even, i.e. a multiple of 2, due to how the limit is stored internally. Storages which are already bigger than the new limit are left as is. Storages with a fixed capacity, such as `ArrayVec`, are never filled past that capacity, even if the limit is higher.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.chain.set_limit(limit);
+        // Storages which were full under the old limit might have room under the new one
+        self.first_free = 0;
+    }
+    /// Returns the currently set limit to which a buffer will be used before an additional allocation will be performed.
+    pub fn limit(&self) -> usize {
+        self.chain.limit()
+    }
+    /// Sets whether additional buffers will be allocated to the limit right away or will first allocate as much as needed and only then rellocate to the limit. Enabled by default.
+    pub fn allocate_to_limit(&mut self, allocate_to_limit: bool) {
+        self.chain.allocate_to_limit(allocate_to_limit);
+    }
+    /// Returns whether [`allocate_to_limit`] is enabled.
+    ///
+    /// [`allocate_to_limit`]: #method.allocate_to_limit " "
+    pub fn allocates_to_limit(&self) -> bool {
+        self.chain.allocates_to_limit()
+    }
+    /// Returns the number of separate storages used.
+    pub fn num_storages(&self) -> usize {
+        self.chain.num_storages()
+    }
+    /// Returns the number of holes in all storages combined.
+    pub fn num_holes(&self) -> usize {
+        // The chain counts the holes as well
+        self.chain.len() - self.len
+    }
+    /// Returns the underlying chain of sparse storages.
+    ///
+    /// The lengths of the storages of the chain count the holes, so a position in the chain is the position of a slot across all storages, and indexing the chain at a hole panics just like indexing a `SparseStorage` does. Iterating over the elements of the chain skips the holes. Only shared access is given out, since shifting the elements of the chain would invalidate the keys.
+    pub fn as_chain(&self) -> &Chain<E, SparseStorage<E, S, B>, I, P> {
+        &self.chain
+    }
+    /// Returns the position of the element with the specified key in the [underlying chain], or `None` if the key doesn't point to an element. Takes *O(log n)* time, where *n* is the number of storages.
+    ///
+    /// [underlying chain]: #method.as_chain " "
+    pub fn position_of(&self, key: &SparseChainKey) -> Option<usize> {
+        if self.contains_key(key) {
+            Some(self.chain.lengths.prefix(key.storage) + key.index)
+        } else {
+            None
+        }
+    }
+    /// Returns the key of the element at the specified position in the [underlying chain], or `None` if there's a hole there or the position is out of bounds. Takes *O(log n)* time, where *n* is the number of storages.
+    ///
+    /// [underlying chain]: #method.as_chain " "
+    pub fn key_at(&self, position: usize) -> Option<SparseChainKey> {
+        let (storage, index) = self.chain.locate(position)?;
+        let key = SparseChainKey { storage, index };
+        if self.contains_key(&key) {
+            Some(key)
+        } else {
+            None
+        }
+    }
+    /// Returns the amount of elements which can be added to the specified storage without exceeding the limit.
+    fn room_in_storage(&self, st: &SparseStorage<E, S, B>) -> usize {
+        st.num_holes() + self.chain.storage_limit().saturating_sub(st.len())
+    }
+    /// Returns the index of the first storage which has room for another element, or `None` if a new storage has to be created for it. Skips over the storages without room for good.
+    fn find_room(&mut self) -> Option<usize> {
+        while let Some(st) = self.chain.contents.get(self.first_free) {
+            if self.room_in_storage(st) != 0 {
+                return Some(self.first_free);
+            }
+            self.first_free += 1;
+        }
+        None
+    }
+    /// Returns the amount of elements which can be added before a new storage has to be created.
+    fn room_before_new_storage(&self) -> usize {
+        let mut room = 0;
+        for st in self.chain.contents.iter().skip(self.first_free) {
+            room += self.room_in_storage(st);
+        }
+        room
+    }
+    /// Returns the amount of storages which have to be created to fit the specified amount of elements, beyond the room in the existing storages.
+    fn storages_needed_for(&self, additional: usize) -> usize {
+        let limit = self.chain.storage_limit();
+        let missing = additional.saturating_sub(self.room_before_new_storage());
+        missing / limit + usize::from(missing % limit != 0)
+    }
+}
+static KEY_PANIC_MSG: &str = "\
+the specified key does not point to an element";
+unsafe impl<E, S, B, I, P> Storage for SparseChain<E, S, B, I, P>
+where
+    S: List<Element = Slot<E>> + DenseList,
+    B: ListStorage<Element = usize>,
+    I: List<Element = SparseStorage<E, S, B>>,
+    P: ListStorage<Element = usize>,
+    SparseStorage<E, S, B>: List<Element = E>,
+{
+    type Key = SparseChainKey;
+    type Element = E;
+    const CAPACITY: Option<usize> = {
+        if let (Some(index_capacity), Some(buffer_capacity)) = (I::CAPACITY, S::CAPACITY) {
+            Some(index_capacity * buffer_capacity)
+        } else {
+            None
+        }
+    };
+
+    fn add(&mut self, element: Self::Element) -> Self::Key {
+        let storage = if let Some(x) = self.find_room() {
+            x
+        } else {
+            self.chain.push_storage();
+            self.chain.num_storages() - 1
+        };
+        let index = self
+            .chain
+            .modify_storage(storage, |st| ListStorage::add(st, element));
+        self.len += 1;
+        SparseChainKey { storage, index }
+    }
+    #[track_caller]
+    fn remove(&mut self, key: &Self::Key) -> Self::Element {
+        let element = if key.storage < self.chain.num_storages() {
+            self.chain.modify_storage(key.storage, |st| st.take(key.index))
+        } else {
+            None
+        }
+        .expect(KEY_PANIC_MSG);
+        self.len -= 1;
+        if key.storage < self.first_free {
+            self.first_free = key.storage;
+        }
+        element
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn with_capacity(capacity: usize) -> Self {
+        let mut storage = Self::new();
+        storage.reserve(capacity);
+        storage
+    }
+    unsafe fn get_unchecked(&self, key: &Self::Key) -> &Self::Element {
+        self.chain
+            .contents
+            .get_unchecked(key.storage)
+            .get_unchecked(key.index)
+    }
+    unsafe fn get_unchecked_mut(&mut self, key: &Self::Key) -> &mut Self::Element {
+        self.chain
+            .contents
+            .get_unchecked_mut(key.storage)
+            .get_unchecked_mut(key.index)
+    }
+    fn contains_key(&self, key: &Self::Key) -> bool {
+        self.get(key).is_some()
+    }
+    fn get(&self, key: &Self::Key) -> Option<&Self::Element> {
+        self.chain.contents.get(key.storage)?.try_get(key.index)
+    }
+    fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Element> {
+        self.chain
+            .contents
+            .get_mut(key.storage)?
+            .try_get_mut(key.index)
+    }
+    fn new() -> Self {
+        let mut chain: Chain<E, SparseStorage<E, S, B>, I, P> = ListStorage::new();
+        chain.set_limit(Self::DEFAULT_LIMIT);
+        Self {
+            chain,
+            len: 0,
+            first_free: 0,
+        }
+    }
+    fn capacity(&self) -> usize {
+        ListStorage::capacity(&self.chain)
+    }
+    /// Reserves room in the storages which have holes or haven't been filled up to the limit before creating new ones, only creating as many as needed to fit the rest. The new storages are allocated up to the limit, since they will be filled up to it before any other storage is created.
+    fn reserve(&mut self, additional: usize) {
+        let limit = self.chain.storage_limit();
+        let mut remaining = additional;
+        for index in self.first_free..self.chain.num_storages() {
+            if remaining == 0 {
+                return;
+            }
+            let st = unsafe {
+                // SAFETY: the index is in bounds as per the loop range
+                self.chain.contents.get_unchecked_mut(index)
+            };
+            // Filling holes doesn't need any capacity
+            remaining -= st.num_holes().min(remaining);
+            let room = limit.saturating_sub(st.len()).min(remaining);
+            ListStorage::reserve(st, room);
+            remaining -= room;
+        }
+        while remaining != 0 {
+            self.chain.push_allocated_storage();
+            remaining = remaining.saturating_sub(limit);
+        }
+    }
+    /// Removes the storages at the end which only contain holes, since there are no valid keys pointing into them, and shrinks the rest. The holes in other storages are kept to keep the keys stable.
+    fn shrink_to_fit(&mut self) {
+        while let Some(st) = self.chain.contents.get(self.chain.num_storages().wrapping_sub(1)) {
+            if st.len() != st.num_holes() {
+                break;
+            }
+            self.chain.pop_storage();
+        }
+        for index in 0..self.chain.num_storages() {
+            self.chain.modify_storage(index, ListStorage::shrink_to_fit);
+        }
+        self.chain.contents.shrink_to_fit();
+        self.chain.lengths.shrink_to_fit();
+        if self.first_free > self.chain.num_storages() {
+            self.first_free = self.chain.num_storages();
+        }
+    }
+    fn try_add(
+        &mut self,
+        element: Self::Element,
+    ) -> Result<Self::Key, CapacityError<Self::Element>> {
+        let storage = if let Some(x) = self.find_room() {
+            x
+        } else {
+            if self.chain.try_push_storage().is_err() {
+                return Err(CapacityError::new(element));
+            }
+            self.chain.num_storages() - 1
+        };
+        let index = self
+            .chain
+            .modify_storage(storage, |st| ListStorage::try_add(st, element))?;
+        self.len += 1;
+        Ok(SparseChainKey { storage, index })
+    }
+    /// Reserves room in the same storages as `reserve`, but checks that the index storages can fit the new storages before creating any of them.
+    ///
+    /// If an error is returned, the sparse chain has the same elements, holes and storages as before the call. Storages which gained capacity before the error, including the index storages, keep it.
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        let limit = self.chain.storage_limit();
+        let num_storages = self.storages_needed_for(additional);
+        self.chain.contents.try_reserve(num_storages)?;
+        self.chain.lengths.try_reserve(num_storages)?;
+        let mut remaining = additional;
+        for index in self.first_free..self.chain.num_storages() {
+            if remaining == 0 {
+                break;
+            }
+            let st = unsafe {
+                // SAFETY: the index is in bounds as per the loop range
+                self.chain.contents.get_unchecked_mut(index)
+            };
+            remaining -= st.num_holes().min(remaining);
+            let room = limit.saturating_sub(st.len()).min(remaining);
+            ListStorage::try_reserve(st, room)?;
+            remaining -= room;
+        }
+        self.chain
+            .append_reserved_storages(repeat(limit).take(num_storages))
+    }
+}
+impl<'a, E, S, B, I, P> StorageIter<'a> for SparseChain<E, S, B, I, P>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+    P: ListStorage<Element = usize> + 'a,
+    SparseStorage<E, S, B>: List<Element = E>,
+{
+    type Keys = Map<SparseChainIter<'a, E, S, B, I>, fn((SparseChainKey, &'a E)) -> SparseChainKey>;
+    type Values = Map<SparseChainIter<'a, E, S, B, I>, fn((SparseChainKey, &'a E)) -> &'a E>;
+    type ValuesMut =
+        Map<SparseChainIterMut<'a, E, S, B, I>, fn((SparseChainKey, &'a mut E)) -> &'a mut E>;
+    type Iter = SparseChainIter<'a, E, S, B, I>;
+    type IterMut = SparseChainIterMut<'a, E, S, B, I>;
+
+    fn keys(&'a self) -> Self::Keys {
+        StorageIter::iter(self).map(|(key, _)| key)
+    }
+    fn values(&'a self) -> Self::Values {
+        StorageIter::iter(self).map(|(_, x)| x)
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        StorageIter::iter_mut(self).map(|(_, x)| x)
+    }
+    fn iter(&'a self) -> Self::Iter {
+        SparseChainIter {
+            storages: self.chain.contents.iter().enumerate(),
+            current: None,
+            remaining: self.len,
+        }
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        SparseChainIterMut {
+            storages: self.chain.contents.iter_mut().enumerate(),
+            current: None,
+            remaining: self.len,
+        }
+    }
+}
+
+/// A key into a [`SparseChain`], consisting of the index of the storage and the index of the element inside of it.
+///
+/// [`SparseChain`]: struct.SparseChain.html " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SparseChainKey {
+    /// The index of the storage which contains the element.
+    pub storage: usize,
+    /// The index of the element inside of its storage.
+    pub index: usize,
+}
+
+type StorageElements<'a, E, S, B> = Indexed<SlotIter<'a, E, <S as IntoRefIterator<'a>>::Iter, B>>;
+type StorageElementsMut<'a, E, S, B> =
+    Indexed<SlotIterMut<'a, E, <S as IntoMutIterator<'a>>::IterMut, B>>;
+
+/// An iterator over the keys of the elements of a [`SparseChain`] and references to them, skipping holes.
+///
+/// Created by the `iter` method from [`StorageIter`].
+///
+/// [`SparseChain`]: struct.SparseChain.html " "
+/// [`StorageIter`]: ../trait.StorageIter.html " "
+pub struct SparseChainIter<'a, E, S, B, I>
+where
+    S: List<Element = Slot<E>> + DenseList,
+    B: ListStorage<Element = usize>,
+    I: List<Element = SparseStorage<E, S, B>>,
+{
+    storages: Enumerate<<I as IntoRefIterator<'a>>::Iter>,
+    current: Option<(usize, StorageElements<'a, E, S, B>)>,
+    remaining: usize,
+}
+impl<'a, E, S, B, I> Iterator for SparseChainIter<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+{
+    type Item = (SparseChainKey, &'a E);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining != 0 {
+            if let Some((storage, elements)) = &mut self.current {
+                if let Some((index, element)) = elements.next() {
+                    self.remaining -= 1;
+                    let key = SparseChainKey {
+                        storage: *storage,
+                        index,
+                    };
+                    return Some((key, element));
+                }
+            }
+            let (storage, st) = self.storages.next()?;
+            self.current = Some((storage, IntoRefIterator::iter(st).indexed()));
+        }
+        None
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, E, S, B, I> ExactSizeIterator for SparseChainIter<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+{
+}
+impl<'a, E, S, B, I> FusedIterator for SparseChainIter<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+{
+}
+impl<'a, E, S, B, I> Clone for SparseChainIter<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+    <S as IntoRefIterator<'a>>::Iter: Clone,
+    <I as IntoRefIterator<'a>>::Iter: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storages: self.storages.clone(),
+            current: self.current.clone(),
+            remaining: self.remaining,
+        }
+    }
+}
+impl<'a, E, S, B, I> Debug for SparseChainIter<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList,
+    B: ListStorage<Element = usize>,
+    I: List<Element = SparseStorage<E, S, B>>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("chain::SparseChainIter")
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An iterator over the keys of the elements of a [`SparseChain`] and mutable references to them, skipping holes.
+///
+/// Created by the `iter_mut` method from [`StorageIter`].
+///
+/// [`SparseChain`]: struct.SparseChain.html " "
+/// [`StorageIter`]: ../trait.StorageIter.html " "
+pub struct SparseChainIterMut<'a, E, S, B, I>
+where
+    S: List<Element = Slot<E>> + DenseList,
+    B: ListStorage<Element = usize>,
+    I: List<Element = SparseStorage<E, S, B>>,
+{
+    storages: Enumerate<<I as IntoMutIterator<'a>>::IterMut>,
+    current: Option<(usize, StorageElementsMut<'a, E, S, B>)>,
+    remaining: usize,
+}
+impl<'a, E, S, B, I> Iterator for SparseChainIterMut<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+{
+    type Item = (SparseChainKey, &'a mut E);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining != 0 {
+            if let Some((storage, elements)) = &mut self.current {
+                if let Some((index, element)) = elements.next() {
+                    self.remaining -= 1;
+                    let key = SparseChainKey {
+                        storage: *storage,
+                        index,
+                    };
+                    return Some((key, element));
+                }
+            }
+            let (storage, st) = self.storages.next()?;
+            self.current = Some((storage, IntoMutIterator::iter_mut(st).indexed()));
+        }
+        None
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, E, S, B, I> ExactSizeIterator for SparseChainIterMut<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+{
+}
+impl<'a, E, S, B, I> FusedIterator for SparseChainIterMut<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+    I: List<Element = SparseStorage<E, S, B>> + 'a,
+{
+}
+impl<'a, E, S, B, I> Debug for SparseChainIterMut<'a, E, S, B, I>
+where
+    E: 'a,
+    S: List<Element = Slot<E>> + DenseList,
+    B: ListStorage<Element = usize>,
+    I: List<Element = SparseStorage<E, S, B>>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("chain::SparseChainIterMut")
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
@@ -13,10 +13,10 @@ impl UsizeAndFlag {
         (self.0 & Self::FLAG_MASK) != 0
     }
     pub fn set_size(&mut self, size: usize) {
-        *self = Self::new(size, self.flag())
+        *self = Self::new(size, self.flag());
     }
     pub fn set_flag(&mut self, flag: bool) {
-        *self = Self::new(self.size(), flag)
+        *self = Self::new(self.size(), flag);
     }
     pub const fn new(size: usize, flag: bool) -> Self {
         Self((size & Self::SIZE_MASK) | (flag as usize))
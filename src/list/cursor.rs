@@ -0,0 +1,161 @@
+use core::fmt::{self, Debug, Formatter};
+use super::{ListStorage, MoveFix};
+
+/// A cursor over a [`ListStorage`] with editing operations, for making sequential edits at a moving position.
+///
+/// The cursor points either at an element or at the *ghost* position, which sits between the last and the first element of the list: moving forward from the last element or backward from the first one reaches the ghost position, and moving past it wraps around to the other end. The ghost position has the index equal to the length of the list, which means that a cursor over an empty list is always at the ghost position.
+///
+/// The `_and_shiftfix` variants of the editing methods notify the elements through [`MoveFix`] by calling the `_and_shiftfix` methods of the list, so a list which implements them in a special way, like [`SparseStorage`], keeps doing so. The cursor stays at the same index after removing with `remove_current_and_shiftfix`, which points to whatever the list puts there — the next element for most lists, or a hole for `SparseStorage`, in which case [`current`] returns `None`.
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, ListCursorMut};
+///
+/// let mut list = vec![1, 2, 3, 4];
+/// let mut cursor = ListCursorMut::new(&mut list, 0);
+/// while let Some(&mut value) = cursor.current() {
+///     if value % 2 == 0 {
+///         cursor.insert_after(value * 10);
+///         cursor.move_next();
+///     }
+///     cursor.move_next();
+/// }
+/// // Moving past the last element leads to the ghost position, and from there to the first one
+/// assert_eq!(cursor.index(), None);
+/// cursor.move_next();
+/// assert_eq!(cursor.remove_current(), Some(1));
+/// cursor.insert_before(0);
+/// assert_eq!(cursor.current(), Some(&mut 2));
+/// assert_eq!(list, [0, 2, 20, 3, 4, 40]);
+/// ```
+///
+/// [`ListStorage`]: trait.ListStorage.html " "
+/// [`MoveFix`]: trait.MoveFix.html " "
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`current`]: #method.current " "
+pub struct ListCursorMut<'a, S: ListStorage> {
+    list: &'a mut S,
+    index: usize,
+}
+impl<'a, S: ListStorage> ListCursorMut<'a, S> {
+    /// Creates a cursor pointing at the element at the specified index, or at the ghost position if the index is equal to the length of the list.
+    ///
+    /// # Panics
+    /// Panics if the index is greater than the length of the list.
+    #[track_caller]
+    pub fn new(list: &'a mut S, index: usize) -> Self {
+        assert!(index <= list.len(), "index out of bounds");
+        Self { list, index }
+    }
+    /// Returns the index of the element the cursor points at, or `None` if it's at the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        if self.index < self.list.len() {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
+    /// Returns a mutable reference to the element the cursor points at, or `None` if it's at the ghost position or there's no element at its index, such as when there's a hole there.
+    pub fn current(&mut self) -> Option<&mut S::Element> {
+        if self.list.contains_index(self.index) {
+            Some(unsafe {
+                // SAFETY: contains_index guarantees that this is safe
+                self.list.get_unchecked_mut(self.index)
+            })
+        } else {
+            None
+        }
+    }
+    /// Moves the cursor to the next element, or to the ghost position if it was at the last one, or to the first element if it was at the ghost position.
+    pub fn move_next(&mut self) {
+        self.index = if self.index >= self.list.len() {
+            0
+        } else {
+            self.index + 1
+        };
+    }
+    /// Moves the cursor to the previous element, or to the ghost position if it was at the first one, or to the last element if it was at the ghost position.
+    pub fn move_prev(&mut self) {
+        self.index = if self.index == 0 {
+            self.list.len()
+        } else {
+            self.index - 1
+        };
+    }
+    /// Returns a shared reference to the list the cursor is over.
+    pub fn as_list(&self) -> &S {
+        self.list
+    }
+    /// Inserts an element before the one the cursor points at, or at the end of the list if it's at the ghost position. The cursor keeps pointing at the same element.
+    pub fn insert_before(&mut self, element: S::Element) {
+        self.list.insert(self.index, element);
+        self.index += 1;
+    }
+    /// Inserts an element after the one the cursor points at, or at the start of the list if it's at the ghost position. The cursor keeps pointing at the same element.
+    pub fn insert_after(&mut self, element: S::Element) {
+        if self.index >= self.list.len() {
+            self.list.insert(0, element);
+            self.index += 1;
+        } else {
+            self.list.insert(self.index + 1, element);
+        }
+    }
+    /// Removes the element the cursor points at and returns it, moving the cursor to the next element, or returns `None` if it's at the ghost position or there's no element at its index, such as when there's a hole there.
+    pub fn remove_current(&mut self) -> Option<S::Element> {
+        if self.list.contains_index(self.index) {
+            Some(self.list.remove(self.index))
+        } else {
+            None
+        }
+    }
+    /// Same as [`insert_before`], but uses `insert_and_shiftfix` to notify the elements of the shift.
+    ///
+    /// [`insert_before`]: #method.insert_before " "
+    pub fn insert_before_and_shiftfix(&mut self, element: S::Element)
+    where
+        S::Element: MoveFix,
+    {
+        self.list.insert_and_shiftfix(self.index, element);
+        self.index += 1;
+    }
+    /// Same as [`insert_after`], but uses `insert_and_shiftfix` to notify the elements of the shift.
+    ///
+    /// [`insert_after`]: #method.insert_after " "
+    pub fn insert_after_and_shiftfix(&mut self, element: S::Element)
+    where
+        S::Element: MoveFix,
+    {
+        if self.index >= self.list.len() {
+            self.list.insert_and_shiftfix(0, element);
+            self.index += 1;
+        } else {
+            self.list.insert_and_shiftfix(self.index + 1, element);
+        }
+    }
+    /// Same as [`remove_current`], but uses `remove_and_shiftfix` to notify the elements of the shift. The cursor stays at the same index, which doesn't necessarily contain the next element afterwards: see the struct-level documentation for details.
+    ///
+    /// [`remove_current`]: #method.remove_current " "
+    pub fn remove_current_and_shiftfix(&mut self) -> Option<S::Element>
+    where
+        S::Element: MoveFix,
+    {
+        if self.list.contains_index(self.index) {
+            let element = self.list.remove_and_shiftfix(self.index);
+            // The list might have discarded the holes at the end
+            if self.index > self.list.len() {
+                self.index = self.list.len();
+            }
+            Some(element)
+        } else {
+            None
+        }
+    }
+}
+impl<S: ListStorage + Debug> Debug for ListCursorMut<'_, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListCursorMut")
+            .field("list", &self.list)
+            .field("index", &self.index)
+            .finish()
+    }
+}
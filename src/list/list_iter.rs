@@ -0,0 +1,133 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+};
+use super::DenseList;
+use crate::{IntoRefIterator, IntoMutIterator};
+
+/// An iterator over the indices of and references to the elements of a [`DenseList`].
+///
+/// Created by the [`StorageIter`] implementation for dense lists. Wraps the iterator of the list itself and counts the indices alongside it, which is correct since dense lists have no holes to skip.
+///
+/// [`DenseList`]: trait.DenseList.html " "
+/// [`StorageIter`]: trait.StorageIter.html " "
+pub struct ListIter<'a, S: DenseList + IntoRefIterator<'a>> {
+    inner: <S as IntoRefIterator<'a>>::Iter,
+    front: usize,
+    back: usize,
+}
+impl<'a, S: DenseList + IntoRefIterator<'a>> ListIter<'a, S> {
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self {
+            inner: IntoRefIterator::iter(storage),
+            front: 0,
+            back: storage.len(),
+        }
+    }
+}
+impl<'a, S: DenseList + IntoRefIterator<'a>> Iterator for ListIter<'a, S> {
+    type Item = (usize, &'a <S as IntoRefIterator<'a>>::Item);
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.inner.next()?;
+        let index = self.front;
+        self.front += 1;
+        Some((index, element))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+impl<'a, S: DenseList + IntoRefIterator<'a>> DoubleEndedIterator for ListIter<'a, S>
+where
+    <S as IntoRefIterator<'a>>::Iter: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let element = self.inner.next_back()?;
+        self.back -= 1;
+        Some((self.back, element))
+    }
+}
+impl<'a, S: DenseList + IntoRefIterator<'a>> ExactSizeIterator for ListIter<'a, S> {}
+impl<'a, S: DenseList + IntoRefIterator<'a>> FusedIterator for ListIter<'a, S> where
+    <S as IntoRefIterator<'a>>::Iter: FusedIterator
+{
+}
+impl<'a, S: DenseList + IntoRefIterator<'a>> Clone for ListIter<'a, S>
+where
+    <S as IntoRefIterator<'a>>::Iter: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+impl<'a, S: DenseList + IntoRefIterator<'a>> Debug for ListIter<'a, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListIter")
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .finish()
+    }
+}
+
+/// An iterator over the indices of and mutable references to the elements of a [`DenseList`].
+///
+/// Created by the [`StorageIter`] implementation for dense lists. Like [`ListIter`], it wraps the mutable iterator of the list itself, so the references it hands out never alias.
+///
+/// [`DenseList`]: trait.DenseList.html " "
+/// [`StorageIter`]: trait.StorageIter.html " "
+/// [`ListIter`]: struct.ListIter.html " "
+pub struct ListIterMut<'a, S: DenseList + IntoMutIterator<'a>> {
+    inner: <S as IntoMutIterator<'a>>::IterMut,
+    front: usize,
+    back: usize,
+}
+impl<'a, S: DenseList + IntoMutIterator<'a>> ListIterMut<'a, S> {
+    pub(crate) fn new(storage: &'a mut S) -> Self {
+        Self {
+            back: storage.len(),
+            inner: IntoMutIterator::iter_mut(storage),
+            front: 0,
+        }
+    }
+}
+impl<'a, S: DenseList + IntoMutIterator<'a>> Iterator for ListIterMut<'a, S> {
+    type Item = (usize, &'a mut <S as IntoMutIterator<'a>>::Item);
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.inner.next()?;
+        let index = self.front;
+        self.front += 1;
+        Some((index, element))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+impl<'a, S: DenseList + IntoMutIterator<'a>> DoubleEndedIterator for ListIterMut<'a, S>
+where
+    <S as IntoMutIterator<'a>>::IterMut: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let element = self.inner.next_back()?;
+        self.back -= 1;
+        Some((self.back, element))
+    }
+}
+impl<'a, S: DenseList + IntoMutIterator<'a>> ExactSizeIterator for ListIterMut<'a, S> {}
+impl<'a, S: DenseList + IntoMutIterator<'a>> FusedIterator for ListIterMut<'a, S> where
+    <S as IntoMutIterator<'a>>::IterMut: FusedIterator
+{
+}
+impl<'a, S: DenseList + IntoMutIterator<'a>> Debug for ListIterMut<'a, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListIterMut")
+            .field("front", &self.front)
+            .field("back", &self.back)
+            .finish()
+    }
+}
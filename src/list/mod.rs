@@ -7,20 +7,45 @@ mod smallvec_impl;
 #[cfg(feature = "tinyvec")]
 mod tinyvec_impl;
 
+mod list_iter;
+pub use list_iter::{ListIter, ListIterMut};
+mod cursor;
+pub use cursor::ListCursorMut;
+
+mod bitmap;
 mod sparse;
-pub use sparse::{SparseStorage, Slot as SparseStorageSlot};
+pub use sparse::{
+    SparseStorage,
+    Slot as SparseStorageSlot,
+    SlotRef as SparseStorageSlotRef,
+    Iter as SparseStorageIter,
+    IterMut as SparseStorageIterMut,
+    Indexed as SparseStorageIndexed,
+    DefragPolicy,
+    HoleReuse,
+};
+#[cfg(feature = "rayon")]
+pub use sparse::{ParIter as SparseStorageParIter, ParIterMut as SparseStorageParIterMut};
+mod bit_sparse;
+pub use bit_sparse::BitSparseStorage;
+#[cfg(feature = "alloc")]
+pub use bit_sparse::Vec as BitSparseVec;
 pub mod chain;
-pub use chain::Chain;
+pub use chain::{Chain, SparseChain, SparseChainKey};
+#[cfg(feature = "alloc")]
+pub use chain::SparseChainVec;
 #[cfg(feature = "alloc")]
-pub use sparse::{Vec as SparseVec, VecDeque as SparseVecDeque};
+pub use sparse::{Vec as SparseVec, VecDeque as SparseVecDeque, KeyRemap};
 
 use core::{
     num::{NonZeroUsize, NonZeroIsize},
     cmp::Ordering,
     hint,
     convert::TryFrom,
+    iter::Map,
+    ops::Range,
 };
-use crate::{IntoMutIterator, IntoRefIterator};
+use crate::{IntoMutIterator, IntoRefIterator, StorageIter, CapacityError};
 
 use super::Storage;
 
@@ -103,6 +128,14 @@ pub unsafe trait ListStorage: Sized {
             None
         }
     }
+    /// Returns `true` if there is an element at position `index` within the collection, `false` otherwise.
+    ///
+    /// The default implementation only performs a bounds check. Collections which can have gaps between their elements, like [`SparseStorage`], override this to also check for those gaps. If this method returned `true`, calling `get_unchecked`/`get_unchecked_mut` on the same index is guaranteed to succeed.
+    ///
+    /// [`SparseStorage`]: struct.SparseStorage.html " "
+    fn contains_index(&self, index: usize) -> bool {
+        self.len() > index
+    }
     /// Creates a new empty collection. Dynamically-allocated collections created this way do not allocate memory.
     ///
     /// The default implementation calls `Self::with_capacity(0)`, which usually doesn't allocate for heap-based storages.
@@ -115,7 +148,7 @@ pub unsafe trait ListStorage: Sized {
     }
     /// Appends an element to the back of the collection.
     fn push(&mut self, element: Self::Element) {
-        self.insert(self.len(), element)
+        self.insert(self.len(), element);
     }
     /// Removes the last element from the collection and returns it, or `None` if it is empty.
     fn pop(&mut self) -> Option<Self::Element> {
@@ -161,6 +194,32 @@ pub unsafe trait ListStorage: Sized {
             self.remove(i);
         }
     }
+    /// Moves the elements starting from position `at` into a new collection, keeping their order, and leaves the first `at` elements in this one.
+    ///
+    /// The default implementation creates the new collection with `with_capacity` and removes the elements from position `at` one by one, pushing each of them into it. This takes quadratic time for collections which shift the elements after the removed one, so implementors are heavily encouraged to override the default behavior.
+    ///
+    /// # Panics
+    /// Required to panic if `at > len()`.
+    #[track_caller]
+    #[must_use = "use `truncate` if you don't need the other half"]
+    fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(at <= len, "index out of bounds");
+        let mut tail = Self::with_capacity(len - at);
+        for _ in at..len {
+            tail.push(self.remove(at));
+        }
+        tail
+    }
+    /// Moves all elements of `other` to the end of the collection, keeping their order and leaving `other` empty.
+    ///
+    /// The default implementation reserves room for the elements and removes them from the start of `other` one by one, pushing each of them into this collection. This takes quadratic time for collections which shift the elements after the removed one, so implementors are heavily encouraged to override the default behavior.
+    fn append(&mut self, other: &mut Self) {
+        self.reserve(other.len());
+        while !other.is_empty() {
+            self.push(other.remove(0));
+        }
+    }
     /// Inserts an element at position `index` within the collection. The items after the inserted item should be notified using the [`MoveFix`] trait or not have their indices changed at all (index changes are not guaranteed and this behavior is implementation-dependent).
     ///
     /// # Panics
@@ -202,6 +261,74 @@ pub unsafe trait ListStorage: Sized {
         self.push(element);
         self.len() - 1
     }
+    /// Inserts an element at position `index` within the collection, shifting all elements after it to the right. This is the fallible counterpart of `insert`.
+    ///
+    /// The default implementation checks the length against [`CAPACITY`] if it's `Some(...)` and calls `insert` if there's room left.
+    ///
+    /// # Errors
+    /// Returns the element back wrapped in a [`CapacityError`] if the collection is full and cannot reallocate.
+    ///
+    /// # Panics
+    /// Required to panic if `index > len()`, regardless of whether there's enough capacity.
+    ///
+    /// [`CapacityError`]: struct.CapacityError.html " "
+    /// [`CAPACITY`]: #associatedconstant.CAPACITY " "
+    #[track_caller]
+    fn try_insert(
+        &mut self,
+        index: usize,
+        element: Self::Element,
+    ) -> Result<(), CapacityError<Self::Element>> {
+        assert!(index <= self.len(), "index out of bounds");
+        if Self::CAPACITY.map_or(false, |capacity| self.len() >= capacity) {
+            Err(CapacityError::new(element))
+        } else {
+            self.insert(index, element);
+            Ok(())
+        }
+    }
+    /// Appends an element to the back of the collection. This is the fallible counterpart of `push`.
+    ///
+    /// The default implementation calls `try_insert` with the current length as the index.
+    ///
+    /// # Errors
+    /// Same as `try_insert`.
+    fn try_push(&mut self, element: Self::Element) -> Result<(), CapacityError<Self::Element>> {
+        self.try_insert(self.len(), element)
+    }
+    /// Adds an element to the collection at an arbitrary index, returning that index. This is the fallible counterpart of [`add`].
+    ///
+    /// The default implementation will call `try_push` and return the index of the element pushed.
+    ///
+    /// # Errors
+    /// Same as `try_insert`.
+    ///
+    /// [`add`]: #method.add " "
+    fn try_add(&mut self, element: Self::Element) -> Result<usize, CapacityError<Self::Element>> {
+        self.try_push(element)?;
+        Ok(self.len() - 1)
+    }
+    /// Reserves capacity for at least `additional` more elements. This is the fallible counterpart of [`reserve`], and never panics due to insufficient capacity.
+    ///
+    /// The default implementation checks the resulting length against [`CAPACITY`] if it's `Some(...)` and calls `reserve` if it fits.
+    ///
+    /// # Errors
+    /// Returns an error if the collection cannot hold that many elements and cannot reallocate, or if the required capacity overflows `usize`.
+    ///
+    /// [`reserve`]: #method.reserve " "
+    /// [`CAPACITY`]: #associatedconstant.CAPACITY " "
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        let fits = self
+            .len()
+            .checked_add(additional)
+            .map_or(false, |required| Self::CAPACITY.map_or(true, |capacity| required <= capacity));
+        if fits {
+            self.reserve(additional);
+            Ok(())
+        } else {
+            Err(CapacityError::new(()))
+        }
+    }
 }
 unsafe impl<T, E> Storage for T
 where
@@ -231,13 +358,7 @@ where
         <Self as ListStorage>::get_unchecked_mut(self, *index)
     }
     fn contains_key(&self, index: &usize) -> bool {
-        <Self as ListStorage>::len(self) > *index
-    }
-    fn get(&self, index: &usize) -> Option<&Self::Element> {
-        <Self as ListStorage>::get(self, *index)
-    }
-    fn get_mut(&mut self, index: &usize) -> Option<&mut Self::Element> {
-        <Self as ListStorage>::get_mut(self, *index)
+        <Self as ListStorage>::contains_index(self, *index)
     }
     fn new() -> Self {
         Self::with_capacity(0)
@@ -246,10 +367,44 @@ where
         <Self as ListStorage>::capacity(self)
     }
     fn reserve(&mut self, additional: usize) {
-        <Self as ListStorage>::reserve(self, additional)
+        <Self as ListStorage>::reserve(self, additional);
     }
     fn shrink_to_fit(&mut self) {
-        <Self as ListStorage>::shrink_to_fit(self)
+        <Self as ListStorage>::shrink_to_fit(self);
+    }
+    fn try_add(&mut self, element: Self::Element) -> Result<usize, CapacityError<Self::Element>> {
+        <Self as ListStorage>::try_add(self, element)
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        <Self as ListStorage>::try_reserve(self, additional)
+    }
+}
+
+impl<'a, T, E> StorageIter<'a> for T
+where
+    T: DenseList<Element = E> + IntoRefIterator<'a, Item = E> + IntoMutIterator<'a, Item = E> + 'a,
+    E: MoveFix + 'a,
+{
+    type Keys = Map<ListIter<'a, T>, fn((usize, &'a E)) -> usize>;
+    type Values = <T as IntoRefIterator<'a>>::Iter;
+    type ValuesMut = <T as IntoMutIterator<'a>>::IterMut;
+    type Iter = ListIter<'a, T>;
+    type IterMut = ListIterMut<'a, T>;
+
+    fn keys(&'a self) -> Self::Keys {
+        ListIter::new(self).map(|(index, _)| index)
+    }
+    fn values(&'a self) -> Self::Values {
+        IntoRefIterator::iter(self)
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        IntoMutIterator::iter_mut(self)
+    }
+    fn iter(&'a self) -> Self::Iter {
+        ListIter::new(self)
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        ListIterMut::new(self)
     }
 }
 
@@ -268,6 +423,19 @@ impl<T> List for T where
         + for<'a> IntoMutIterator<'a, Item = <Self as ListStorage>::Element>
 {
 }
+/// List storages which never have holes: every index below `len()` contains an element, so iterating over the storage yields exactly `len()` elements in the order of their indices.
+///
+/// The [`StorageIter`] implementation for list storages relies on this to pair the elements yielded by the iterators of the storage with their indices. Storages which can have holes, like [`SparseStorage`], implement `StorageIter` with their own indexed iterators instead.
+///
+/// # Safety
+/// The iterators returned by the `IntoRefIterator` and `IntoMutIterator` implementations must yield exactly `len()` elements, with the element at every index yielded at the same position — counting from the front when calling `next` and from the back when calling `next_back`.
+///
+/// If the storage also implements `IntoParRefIterator` or `IntoParMutIterator` with an [indexed parallel iterator], that iterator must likewise yield exactly `len()` elements, with the element at every index yielded at that index of the parallel iterator.
+///
+/// [`StorageIter`]: trait.StorageIter.html " "
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [indexed parallel iterator]: https://docs.rs/rayon/*/rayon/iter/trait.IndexedParallelIterator.html " "
+pub unsafe trait DenseList: ListStorage {}
 
 /// Trait for data structure element types to be able to correct indices towards other elements when they are moved around in the collection.
 ///
@@ -278,7 +446,7 @@ pub trait MoveFix: Sized {
     /// This method is *never* called directly by storages. `fix_left_shift` and `fix_right_shift` are called instead. See those for more on how and when this method gets called.
     ///
     /// # Safety
-    /// This method can ***only*** be called by `fix_left_shift` and `fix_right_shift`. All safety implications of those methods apply.
+    /// This method can ***only*** be called by `fix_left_shift`, `fix_right_shift` and the default implementation of `fix_range_shift`. All safety implications of those methods apply.
     unsafe fn fix_shift<S>(storage: &mut S, shifted_from: usize, shifted_by: NonZeroIsize)
     where
         S: ListStorage<Element = Self>;
@@ -332,6 +500,146 @@ pub trait MoveFix: Sized {
             .expect("unexpected integer overflow"),
         );
     }
+    /// The hook to be called when multiple elements get moved at once, such as during defragmentation. `moves` contains pairs of previous and current indices, in the order in which the elements were moved, and every element appears in it at most once. All of the moves have already been performed when this is called.
+    ///
+    /// The default implementation calls `fix_move` for every pair. Implementors which have to walk the entire storage to fix an index can override this to fix all of them in a single pass.
+    ///
+    /// # Safety
+    /// Same as `fix_move`, for every pair.
+    unsafe fn fix_moves<S>(storage: &mut S, moves: &[(usize, usize)])
+    where
+        S: ListStorage<Element = Self>,
+    {
+        for &(previous_index, current_index) in moves {
+            Self::fix_move(storage, previous_index, current_index);
+        }
+    }
+    /// The hook to be called when all elements in a range get shifted by the same amount, while the ones outside of it stay in place. `range` contains the indices of the elements *before* the shift, and `shifted_by` is added to each of them to get their current indices.
+    ///
+    /// If the range extends to the end of the storage, no element after it stays in place, so the default implementation reports the shift with a single call to `fix_shift`, just like `fix_left_shift` and `fix_right_shift` would. Otherwise, it calls `fix_move` for every element in the range, starting from the end of the range when shifting to the right, so that no element is moved to an index from which another one is yet to be moved. Implementors which have to walk the entire storage to fix an index can override this to fix all of them in a single pass.
+    ///
+    /// # Safety
+    /// The implementor of this method may cause undefined behavior if the method was called erroneously and elements were not actually shifted.
+    #[allow(clippy::cast_sign_loss)] // Wrapping addition of a negative shift in two's complement subtracts
+    unsafe fn fix_range_shift<S>(storage: &mut S, range: Range<usize>, shifted_by: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        let shift = |index: usize| index.wrapping_add(shifted_by.get() as usize);
+        if shift(range.end) == storage.len() {
+            // A shift to the left starts where the first removed element was
+            let shifted_from = if shifted_by.get() < 0 {
+                shift(range.start)
+            } else {
+                range.start
+            };
+            Self::fix_shift(storage, shifted_from, shifted_by);
+        } else if shifted_by.get() > 0 {
+            for previous_index in range.rev() {
+                Self::fix_move(storage, previous_index, shift(previous_index));
+            }
+        } else {
+            for previous_index in range {
+                Self::fix_move(storage, previous_index, shift(previous_index));
+            }
+        }
+    }
+}
+
+/// Collects the moves performed by defragmentation to notify the elements of them using `MoveFix::fix_moves`.
+///
+/// With the `alloc` feature, all moves are reported with a single call unless an element is moved twice. Otherwise, there's nowhere to put an arbitrary amount of them, so they are reported in fixed-size batches instead.
+struct MoveBatch {
+    #[cfg(feature = "alloc")]
+    moves: alloc::vec::Vec<(usize, usize)>,
+    #[cfg(not(feature = "alloc"))]
+    moves: [(usize, usize); MoveBatch::CAPACITY],
+    #[cfg(not(feature = "alloc"))]
+    len: usize,
+    /// The highest index to which a recorded move has put an element.
+    max_current: Option<usize>,
+}
+impl MoveBatch {
+    #[cfg(not(feature = "alloc"))]
+    const CAPACITY: usize = 64;
+
+    /// Creates a batch for the specified maximum amount of moves, which is only used to preallocate memory.
+    #[allow(unused_variables, clippy::missing_const_for_fn)] // Only unused and const without alloc
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            #[cfg(feature = "alloc")]
+            moves: alloc::vec::Vec::with_capacity(capacity),
+            #[cfg(not(feature = "alloc"))]
+            moves: [(0, 0); Self::CAPACITY],
+            #[cfg(not(feature = "alloc"))]
+            len: 0,
+            max_current: None,
+        }
+    }
+    /// Reports the recorded moves if the element at the specified index might have been put there by one of them, since `fix_moves` is never told about the same element twice. Has to be called before moving an element which might have already been moved.
+    ///
+    /// # Safety
+    /// Same as `push`.
+    unsafe fn prepare_move<S>(&mut self, storage: &mut S, previous_index: usize)
+    where
+        S: ListStorage,
+        S::Element: MoveFix,
+    {
+        if self.max_current.map_or(false, |max| previous_index <= max) {
+            self.flush(storage);
+        }
+    }
+    /// Records the move of an element, reporting the recorded moves right away if that fills up the batch.
+    ///
+    /// # Safety
+    /// The element must have actually been moved.
+    #[allow(unused_variables)] // Only used without alloc
+    unsafe fn push<S>(&mut self, storage: &mut S, previous_index: usize, current_index: usize)
+    where
+        S: ListStorage,
+        S::Element: MoveFix,
+    {
+        debug_assert!(
+            self.max_current.map_or(true, |max| previous_index > max),
+            "element moved twice without prepare_move",
+        );
+        #[cfg(feature = "alloc")]
+        self.moves.push((previous_index, current_index));
+        #[cfg(not(feature = "alloc"))]
+        {
+            self.moves[self.len] = (previous_index, current_index);
+            self.len += 1;
+        }
+        self.max_current = Some(
+            self.max_current
+                .map_or(current_index, |max| max.max(current_index)),
+        );
+        // Reported right away so that the hooks never see a move which hasn't been recorded yet
+        #[cfg(not(feature = "alloc"))]
+        if self.len == Self::CAPACITY {
+            self.flush(storage);
+        }
+    }
+    /// Reports the recorded moves.
+    ///
+    /// # Safety
+    /// Same as `push`.
+    unsafe fn flush<S>(&mut self, storage: &mut S)
+    where
+        S: ListStorage,
+        S::Element: MoveFix,
+    {
+        self.max_current = None;
+        #[cfg(feature = "alloc")]
+        let moves = &mut self.moves;
+        #[cfg(not(feature = "alloc"))]
+        let moves = &mut self.moves[..core::mem::replace(&mut self.len, 0)];
+        if !moves.is_empty() {
+            S::Element::fix_moves(storage, moves);
+        }
+        #[cfg(feature = "alloc")]
+        moves.clear();
+    }
 }
 
 /// Wrapper around a type which implements `MoveFix` by doing nothing when notified.
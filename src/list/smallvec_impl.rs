@@ -1,6 +1,6 @@
 use smallvec::{SmallVec, Array};
 use core::slice;
-use crate::{ListStorage, IntoRefIterator, IntoMutIterator};
+use crate::{ListStorage, IntoRefIterator, IntoMutIterator, DenseList, CapacityError};
 
 unsafe impl<A: Array> ListStorage for SmallVec<A> {
     type Element = A::Item;
@@ -9,7 +9,7 @@ unsafe impl<A: Array> ListStorage for SmallVec<A> {
         Self::with_capacity(capacity)
     }
     fn insert(&mut self, index: usize, element: Self::Element) {
-        self.insert(index, element)
+        self.insert(index, element);
     }
     fn remove(&mut self, index: usize) -> Self::Element {
         self.remove(index)
@@ -34,7 +34,7 @@ unsafe impl<A: Array> ListStorage for SmallVec<A> {
         Self::new()
     }
     fn push(&mut self, element: Self::Element) {
-        self.push(element)
+        self.push(element);
     }
     fn pop(&mut self) -> Option<Self::Element> {
         self.pop()
@@ -43,13 +43,23 @@ unsafe impl<A: Array> ListStorage for SmallVec<A> {
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
-        self.shrink_to_fit()
+        self.shrink_to_fit();
     }
     fn truncate(&mut self, len: usize) {
-        self.truncate(len)
+        self.truncate(len);
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        self.drain(at..).collect()
+    }
+    fn append(&mut self, other: &mut Self) {
+        self.append(other);
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        self.try_reserve(additional)
+            .map_err(|_| CapacityError::new(()))
     }
 }
 impl<'a, A: Array> IntoRefIterator<'a> for SmallVec<A>
@@ -72,3 +82,4 @@ where
         self.as_mut_slice().iter_mut()
     }
 }
+unsafe impl<A: Array> DenseList for SmallVec<A> {}
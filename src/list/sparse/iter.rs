@@ -0,0 +1,338 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    iter::FusedIterator,
+    marker::PhantomData,
+};
+use super::Slot;
+use crate::{list::bitmap::Bitmap, ListStorage};
+
+/// An iterator over references to the elements of a [`SparseStorage`], skipping holes.
+///
+/// Holes are skipped a word of the occupancy bitmap at a time, without touching the slots themselves. Use [`indexed`] to also get the index of each element.
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`indexed`]: #method.indexed " "
+pub struct Iter<'a, E, I, B>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    inner: I,
+    occupancy: &'a Bitmap<B>,
+    front: usize,
+    back: usize,
+    remaining: usize,
+    _phantom: PhantomData<&'a E>,
+}
+impl<'a, E: 'a, I, B> Iter<'a, E, I, B>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    pub(super) fn new(inner: I, occupancy: &'a Bitmap<B>, len: usize, num_elements: usize) -> Self {
+        Self {
+            inner,
+            occupancy,
+            front: 0,
+            back: len,
+            remaining: num_elements,
+            _phantom: PhantomData,
+        }
+    }
+    /// Turns the iterator into one which also yields the index of each element.
+    pub fn indexed(self) -> Indexed<Self> {
+        Indexed(self)
+    }
+    fn next_indexed(&mut self) -> Option<(usize, &'a E)> {
+        let index = self.occupancy.next(true, self.front, self.back)?;
+        // The inner iterator is always positioned at `front`
+        let slot = self.inner.nth(index - self.front)?;
+        self.front = index + 1;
+        self.remaining -= 1;
+        Some((index, unsafe {
+            // SAFETY: the occupancy bit is set
+            slot.element()
+        }))
+    }
+    fn next_back_indexed(&mut self) -> Option<(usize, &'a E)>
+    where
+        I: DoubleEndedIterator,
+    {
+        let index = self.occupancy.prev(true, self.front, self.back)?;
+        // The inner iterator is always positioned at `back` from the end
+        let slot = self.inner.nth_back(self.back - 1 - index)?;
+        self.back = index;
+        self.remaining -= 1;
+        Some((index, unsafe {
+            // SAFETY: as above
+            slot.element()
+        }))
+    }
+}
+impl<'a, E: 'a, I, B> Iterator for Iter<'a, E, I, B>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    type Item = &'a E;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_indexed().map(|(_, element)| element)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, E: 'a, I, B> DoubleEndedIterator for Iter<'a, E, I, B>
+where
+    I: DoubleEndedIterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_back_indexed().map(|(_, element)| element)
+    }
+}
+impl<'a, E: 'a, I, B> ExactSizeIterator for Iter<'a, E, I, B>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E: 'a, I, B> FusedIterator for Iter<'a, E, I, B>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E: 'a, I, B> Clone for Iter<'a, E, I, B>
+where
+    I: Iterator<Item = &'a Slot<E>> + Clone,
+    B: ListStorage<Element = usize>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            occupancy: self.occupancy,
+            front: self.front,
+            back: self.back,
+            remaining: self.remaining,
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<'a, E: 'a, I, B> Debug for Iter<'a, E, I, B>
+where
+    I: Iterator<Item = &'a Slot<E>> + Debug,
+    B: ListStorage<Element = usize>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("sparse::Iter")
+            .field("inner", &self.inner)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An iterator over mutable references to the elements of a [`SparseStorage`], skipping holes.
+///
+/// Holes are skipped a word of the occupancy bitmap at a time, without touching the slots themselves. Use [`indexed`] to also get the index of each element.
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`indexed`]: #method.indexed " "
+pub struct IterMut<'a, E, I, B>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    inner: I,
+    occupancy: &'a Bitmap<B>,
+    front: usize,
+    back: usize,
+    remaining: usize,
+    _phantom: PhantomData<&'a mut E>,
+}
+impl<'a, E: 'a, I, B> IterMut<'a, E, I, B>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    pub(super) fn new(inner: I, occupancy: &'a Bitmap<B>, len: usize, num_elements: usize) -> Self {
+        Self {
+            inner,
+            occupancy,
+            front: 0,
+            back: len,
+            remaining: num_elements,
+            _phantom: PhantomData,
+        }
+    }
+    /// Turns the iterator into one which also yields the index of each element.
+    pub fn indexed(self) -> Indexed<Self> {
+        Indexed(self)
+    }
+    fn next_indexed(&mut self) -> Option<(usize, &'a mut E)> {
+        let index = self.occupancy.next(true, self.front, self.back)?;
+        let slot = self.inner.nth(index - self.front)?;
+        self.front = index + 1;
+        self.remaining -= 1;
+        Some((index, unsafe {
+            // SAFETY: the occupancy bit is set
+            slot.element_mut()
+        }))
+    }
+    fn next_back_indexed(&mut self) -> Option<(usize, &'a mut E)>
+    where
+        I: DoubleEndedIterator,
+    {
+        let index = self.occupancy.prev(true, self.front, self.back)?;
+        let slot = self.inner.nth_back(self.back - 1 - index)?;
+        self.back = index;
+        self.remaining -= 1;
+        Some((index, unsafe {
+            // SAFETY: as above
+            slot.element_mut()
+        }))
+    }
+}
+impl<'a, E: 'a, I, B> Iterator for IterMut<'a, E, I, B>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    type Item = &'a mut E;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_indexed().map(|(_, element)| element)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, E: 'a, I, B> DoubleEndedIterator for IterMut<'a, E, I, B>
+where
+    I: DoubleEndedIterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_back_indexed().map(|(_, element)| element)
+    }
+}
+impl<'a, E: 'a, I, B> ExactSizeIterator for IterMut<'a, E, I, B>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E: 'a, I, B> FusedIterator for IterMut<'a, E, I, B>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E: 'a, I, B> Debug for IterMut<'a, E, I, B>
+where
+    I: Iterator<Item = &'a mut Slot<E>> + Debug,
+    B: ListStorage<Element = usize>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("sparse::IterMut")
+            .field("inner", &self.inner)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An adapter for the iterators of a [`SparseStorage`] which also yields the index of each element.
+///
+/// Created by the `indexed` method on [`Iter`] and [`IterMut`].
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, IntoRefIterator, SparseVec, DummyMoveFix};
+///
+/// let mut storage = SparseVec::<DummyMoveFix<char>>::new();
+/// for c in "abcd".chars() {
+///     storage.add(c.into());
+/// }
+/// storage.remove_and_shiftfix(0);
+/// storage.remove_and_shiftfix(2);
+///
+/// let iter = storage.iter();
+/// assert_eq!(iter.len(), 2);
+/// let indexed = iter.indexed().map(|(i, c)| (i, c.0)).collect::<Vec<_>>();
+/// assert_eq!(indexed, [(1, 'b'), (3, 'd')]);
+/// let reversed = storage.iter().rev().map(|c| c.0).collect::<String>();
+/// assert_eq!(reversed, "db");
+/// ```
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`Iter`]: struct.SparseStorageIter.html " "
+/// [`IterMut`]: struct.SparseStorageIterMut.html " "
+#[derive(Copy, Clone, Debug)]
+pub struct Indexed<I>(I);
+impl<'a, E: 'a, I, B> Iterator for Indexed<Iter<'a, E, I, B>>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    type Item = (usize, &'a E);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_indexed()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+impl<'a, E: 'a, I, B> DoubleEndedIterator for Indexed<Iter<'a, E, I, B>>
+where
+    I: DoubleEndedIterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back_indexed()
+    }
+}
+impl<'a, E: 'a, I, B> ExactSizeIterator for Indexed<Iter<'a, E, I, B>>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E: 'a, I, B> FusedIterator for Indexed<Iter<'a, E, I, B>>
+where
+    I: Iterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E: 'a, I, B> Iterator for Indexed<IterMut<'a, E, I, B>>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    type Item = (usize, &'a mut E);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_indexed()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+impl<'a, E: 'a, I, B> DoubleEndedIterator for Indexed<IterMut<'a, E, I, B>>
+where
+    I: DoubleEndedIterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back_indexed()
+    }
+}
+impl<'a, E: 'a, I, B> ExactSizeIterator for Indexed<IterMut<'a, E, I, B>>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E: 'a, I, B> FusedIterator for Indexed<IterMut<'a, E, I, B>>
+where
+    I: Iterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
@@ -0,0 +1,1482 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    ptr,
+    mem::{self, size_of, MaybeUninit},
+    num::NonZeroUsize,
+    hint,
+    iter::Map,
+};
+use super::{bitmap::Bitmap, DenseList, List, ListStorage, MoveFix, MoveBatch};
+use crate::{IntoRefIterator, IntoMutIterator, StorageIter, CapacityError};
+#[cfg(feature = "rayon")]
+use rayon::iter::IndexedParallelIterator;
+#[cfg(feature = "rayon")]
+use crate::{IntoParRefIterator, IntoParMutIterator};
+
+mod iter;
+pub use iter::{Iter, IterMut, Indexed};
+#[cfg(feature = "rayon")]
+mod par_iter;
+#[cfg(feature = "rayon")]
+pub use par_iter::{ParIter, ParIterMut};
+mod policy;
+pub use policy::{DefragPolicy, HoleReuse};
+#[cfg(feature = "alloc")]
+mod remap;
+#[cfg(feature = "alloc")]
+pub use remap::KeyRemap;
+
+/// A `Vec` wrapped in [`SparseStorage`], with the occupancy bitmap also stored in a `Vec`.
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "alloc")))]
+pub type Vec<T> = SparseStorage<T, alloc::vec::Vec<Slot<T>>, alloc::vec::Vec<usize>>;
+/// A `VecDeque` wrapped in [`SparseStorage`], with the occupancy bitmap stored in a `Vec`.
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "alloc")))]
+pub type VecDeque<T> =
+    SparseStorage<T, alloc::collections::VecDeque<Slot<T>>, alloc::vec::Vec<usize>>;
+
+/// A wrapper around a list-like storage type which considerably improves performance when removing elements.
+///
+/// Sparse storage with element type `E` wraps a normal storage which stores `Slot<E>`, which either contains an element or is a "hole". Those holes count as regular elements, but trying to get their value produces a panic, since the storage provides `E` as its element type, rather than `Slot<E>`. This behavior does not depend on whether checked or unchecked `get`/`get_mut` methods are used - all of those are guaranteed to panic upon fetching a hole.
+///
+/// When `remove_and_shiftfix` is called, elements are not actually shifted, but the element is replaced with a hole. If the elements of the storage store indicies towards other elements of the storage, they don't get invalidated.
+///
+/// The raw `remove` and `pop` methods work regardless of holes, so that sparse storage can be used anywhere a `ListStorage` is expected: `remove` shifts the elements after the removed one to the left like it would with any other list storage, while `pop` discards the holes at the end of the storage and returns the last element.
+///
+/// Which slots are holes is recorded in a bitmap with one bit per slot instead of inside the slots, so that slots are exactly as big as the elements. The bitmap also serves as the index of free slots for the [`LowestIndex`] hole reuse strategy. The other strategies find the hole to fill through a doubly linked list of holes: holes of elements which are at least two pointers big store the links in the bytes of the element, while smaller elements have no room for them. Those start off with the `LowestIndex` strategy instead, which needs nothing but the bitmap, and only keep the links in a separate table of two words per slot once switched to another strategy. See [`Slot`] for more on the layout, and [`BitSparseStorage`] for a variant which never keeps the hole list.
+///
+/// The bitmap and the link table are kept in storages of `usize` words, which is the third generic argument, so that the sparse storage can be used without an allocator. A fixed-capacity word storage must be able to hold a word for every `usize::BITS` slots, plus a summary word for every `usize::BITS` words, and if the elements are smaller than two pointers and the hole reuse strategy was ever switched away from `LowestIndex`, it must also be able to hold two words for every slot.
+///
+/// # Example
+/// ```rust
+/// use granite::{
+///     ListStorage, // ListStorage trait, for interfacing with the sparse
+///                  // storage's generic list-like storage capabilities
+///     SparseVec, // A sparse storage type definition for Vec
+///     DummyMoveFix, // Utility struct to have elements which can be removed with
+///                   // a move fix but which actually don't react to the hooks at all.
+/// };
+///
+/// // Use the new() method from the Storage trait to create a sparse storage:
+/// let mut storage = SparseVec::<DummyMoveFix<u32>>::new();
+/// // Or, the with_capacity() method to preallocate some space for the elements:
+/// let mut storage = SparseVec::<DummyMoveFix<u32>>::with_capacity(32);
+///
+/// // Let's put some elements in!
+/// storage.add(0.into());
+/// storage.add(1.into());
+/// storage.add(2.into());
+///
+/// // Now that we have some elements, we can remove the one in the
+/// // middle to create a hole, and see what sparse storage is about.
+/// let val = storage.remove_and_shiftfix(1);
+/// assert_eq!(val, 1);
+///
+/// // Let's check the length of the storage:
+/// let len = storage.len();
+/// // It hasn't changed, because the element from the wrapped storage wasn't actually removed...
+/// assert_eq!(len, 3);
+///
+/// // ...but instead dropped and turned into a hole:
+/// let num_holes = storage.num_holes();
+/// assert_eq!(num_holes, 1);
+///
+/// // Let's remove yet another element:
+/// let val = storage.remove_and_shiftfix(2);
+/// assert_eq!(val, 2);
+/// // It's the third element (index 2), because removing the
+/// // previous one did not shift the elements from their indicies.
+///
+/// // The previous hole is still there, and we have a new one:
+/// let num_holes = storage.num_holes();
+/// assert_eq!(num_holes, 2);
+///
+/// // Let's remove the holes from the storage entirely:
+/// storage.defragment();
+/// // We could've called defragment_and_fix, but since we have
+/// // the dummy move fix wrapper, that wouldn't make a difference.
+///
+/// // The holes are gone!
+/// let num_holes = storage.num_holes();
+/// assert_eq!(num_holes, 0);
+/// // We can also use the is_dense() method to check for this:
+/// assert!(storage.is_dense());
+/// // The method is specific to sparse storage and is not a part of the Storage trait.
+/// ```
+///
+/// [`LowestIndex`]: enum.HoleReuse.html#variant.LowestIndex " "
+/// [`Slot`]: struct.SparseStorageSlot.html " "
+/// [`BitSparseStorage`]: struct.BitSparseStorage.html " "
+pub struct SparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    storage: S,
+    /// One bit per slot, set if the slot contains an element.
+    occupancy: Bitmap<B>,
+    /// Length, first element, last element. Only the length is used if the holes are not linked.
+    hole_list: Option<(NonZeroUsize, usize, usize)>,
+    /// The links of the holes if the elements are too small to store them, two words per slot. Only covers the slots up to the last one which was turned into a hole, and is always empty for big enough elements.
+    link_table: B,
+    /// Whether holes are linked into the hole list at all. Turned off by `BitSparseStorage`, which only ever fills the lowest hole and never writes to holes.
+    linked: bool,
+    defrag_policy: DefragPolicy,
+    hole_reuse: HoleReuse,
+}
+impl<E, S, B> SparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    /// Removes all holes from the sparse storage, *without fixing elements' indicies*. **This is an expensive operation and should only be called if `is_dense` is `false` to avoid needless overhead.**
+    pub fn defragment(&mut self) {
+        self.defragment_impl(|_, _, _| {});
+    }
+    /// Removes all holes from the sparse storage, fixing elements' indicies. **This is an expensive operation and should only be called if `is_dense` is `false` to avoid needless overhead.**
+    ///
+    /// Holes are filled with elements from the end of the storage in a single pass, and every element which was relocated is reported exactly once through `MoveFix::fix_moves`, which calls `MoveFix::fix_move` for each of them unless overridden. With the `alloc` feature, all of them are reported with a single call once the storage is dense. Without it, they are reported in fixed-size batches as the holes are being filled, and the holes which were filled or popped off by then are already gone from the storage, so the hooks can access it like at any other time.
+    ///
+    /// # Example
+    /// ```rust
+    /// use core::num::NonZeroIsize;
+    /// use granite::{ListStorage, IntoRefIterator, MoveFix, SparseVec};
+    ///
+    /// struct Node {
+    ///     value: u32,
+    ///     moved_from: Option<usize>,
+    /// }
+    /// impl MoveFix for Node {
+    ///     unsafe fn fix_shift<S>(_: &mut S, _: usize, _: NonZeroIsize)
+    ///     where
+    ///         S: ListStorage<Element = Self>,
+    ///     {
+    ///     }
+    ///     unsafe fn fix_move<S>(storage: &mut S, previous_index: usize, current_index: usize)
+    ///     where
+    ///         S: ListStorage<Element = Self>,
+    ///     {
+    ///         let node = storage.get_mut(current_index).unwrap();
+    ///         assert!(node.moved_from.is_none(), "element moved twice");
+    ///         node.moved_from = Some(previous_index);
+    ///     }
+    /// }
+    ///
+    /// let mut storage = SparseVec::new();
+    /// for value in 0..6 {
+    ///     storage.add(Node { value, moved_from: None });
+    /// }
+    /// for &index in &[1, 2, 4] {
+    ///     storage.remove_and_shiftfix(index);
+    /// }
+    /// storage.defragment_and_fix();
+    ///
+    /// let nodes = storage
+    ///     .iter()
+    ///     .map(|node| (node.value, node.moved_from))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(nodes, [(0, None), (5, Some(5)), (3, Some(3))]);
+    /// ```
+    pub fn defragment_and_fix(&mut self)
+    where
+        E: MoveFix,
+    {
+        let mut batch = MoveBatch::with_capacity(self.num_holes());
+        self.defragment_impl(|s, i, j| {
+            unsafe {
+                // SAFETY: we just swapped those elements
+                batch.push(s, i, j);
+            }
+        });
+        unsafe {
+            // SAFETY: as above
+            batch.flush(self);
+        }
+    }
+    /// Performs at most `max_moves` steps of defragmentation, fixing elements' indices, and returns `true` if the storage is dense afterwards.
+    ///
+    /// Every step moves the last element of the storage into a single hole, so `max_moves` is the maximum number of elements which get moved. Holes at the end of the storage are popped off before and after every step and don't count towards the budget. The moved elements are reported through `MoveFix::fix_moves` once the steps are done. The storage stays fully usable between calls, so adding, removing and accessing elements can be freely interleaved with an ongoing defragmentation, which makes it possible to spread the cost of defragmenting a large storage across multiple frames or requests.
+    ///
+    /// Every step fills the hole which `add` would fill next: the one which was punched the earliest with the `Fifo` hole reuse strategy, the latest with `Lifo`, and the one with the lowest index with [`LowestIndex`]. `defragment_and_fix` always fills the hole with the lowest index, so the elements only end up in the same order as with it if the strategy is `LowestIndex`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, IntoRefIterator, SparseVec, DummyMoveFix};
+    ///
+    /// let mut storage = SparseVec::<DummyMoveFix<u32>>::new();
+    /// for value in 0..8 {
+    ///     storage.add(value.into());
+    /// }
+    /// for &index in &[1, 3, 5] {
+    ///     storage.remove_and_shiftfix(index);
+    /// }
+    ///
+    /// assert!(!storage.defragment_step(1));
+    /// assert_eq!(storage.num_holes(), 2);
+    /// // The storage can be used in between steps
+    /// let index = storage.add(8.into());
+    /// assert_eq!(storage.get(index).unwrap().0, 8);
+    /// assert!(storage.defragment_step(10));
+    ///
+    /// assert!(storage.is_dense());
+    /// let mut elements = storage.iter().map(|x| x.0).collect::<Vec<_>>();
+    /// elements.sort_unstable();
+    /// assert_eq!(elements, [0, 2, 4, 6, 7, 8]);
+    /// ```
+    ///
+    /// [`LowestIndex`]: enum.HoleReuse.html#variant.LowestIndex " "
+    pub fn defragment_step(&mut self, max_moves: usize) -> bool
+    where
+        E: MoveFix,
+    {
+        let mut batch = MoveBatch::with_capacity(max_moves.min(self.num_holes()));
+        for _ in 0..max_moves {
+            self.pop_trailing_holes();
+            let hole = match self.next_hole() {
+                Some(x) => x,
+                None => break,
+            };
+            unsafe {
+                // SAFETY: all recorded moves have been performed
+                batch.prepare_move(self, self.len() - 1);
+                // SAFETY: hole info always points to holes within bounds, and there are no holes
+                // at the end
+                let last = self.move_last_into(hole);
+                // SAFETY: we just moved the element
+                batch.push(self, last, hole);
+            }
+        }
+        unsafe {
+            // SAFETY: as above
+            batch.flush(self);
+        }
+        self.pop_trailing_holes();
+        self.is_dense()
+    }
+    /// Removes all holes from the sparse storage and returns a table mapping the old indices of the elements to their new ones. **This is an expensive operation and should only be called if `is_dense` is `false` to avoid needless overhead.**
+    ///
+    /// This is useful when indices into the storage are stored outside of it, where `MoveFix` cannot reach them. The elements are relocated the same way as with `defragment`, and no `MoveFix` hooks are called.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, IntoRefIterator, SparseVec, DummyMoveFix};
+    ///
+    /// let mut storage = SparseVec::<DummyMoveFix<char>>::new();
+    /// for c in "abcdef".chars() {
+    ///     storage.add(c.into());
+    /// }
+    /// for &index in &[1, 2, 4] {
+    ///     storage.remove_and_shiftfix(index);
+    /// }
+    /// // Some indices which are stored somewhere else
+    /// let mut external = [0, 3, 5];
+    /// let remap = storage.defragment_with_remap();
+    /// for index in &mut external {
+    ///     *index = remap.get(*index).unwrap();
+    /// }
+    /// assert_eq!(external, [0, 2, 1]);
+    /// assert_eq!(remap.get(1), None); // Holes don't map anywhere
+    /// assert_eq!(remap.moves().collect::<Vec<_>>(), [(5, 1), (3, 2)]);
+    ///
+    /// let elements = storage.iter().map(|c| c.0).collect::<String>();
+    /// assert_eq!(elements, "afd");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "alloc")))]
+    pub fn defragment_with_remap(&mut self) -> KeyRemap {
+        let old_len = self.len();
+        let mut moves = alloc::vec::Vec::new();
+        self.defragment_impl(|_, i, j| moves.push((i, j)));
+        KeyRemap::new(moves, old_len, self.len())
+    }
+    fn defragment_impl(&mut self, mut f: impl FnMut(&mut Self, usize, usize)) {
+        if self.is_dense() {
+            // No holes == nothing to defragment
+            return;
+        }
+        // Every step pops the holes off the end and moves the last element into the lowest hole,
+        // so every element is moved at most once. The hole list and the counters are updated
+        // along the way, which means that `f` always sees a consistent storage. `front` skips
+        // the holes which were already filled, and the search skips entire words of the
+        // occupancy bitmap at a time.
+        let mut front = 0;
+        loop {
+            self.pop_trailing_holes();
+            let hole = match self.occupancy.next(false, front, self.len()) {
+                Some(x) => x,
+                None => break,
+            };
+            let element = unsafe {
+                // SAFETY: the bitmap only has bits for slots within bounds, and there are no holes
+                // at the end
+                self.move_last_into(hole)
+            };
+            f(self, element, hole);
+            front = hole + 1;
+        }
+        // There are no holes left to link
+        self.link_table.truncate(0);
+    }
+    /// Moves the last element into the specified hole and pops off the slot which it was moved out of, returning the previous index of the element.
+    ///
+    /// # Safety
+    /// The specified index must point to a hole within bounds, and the last slot must contain an element.
+    unsafe fn move_last_into(&mut self, hole: usize) -> usize {
+        let last = self.len() - 1;
+        self.unlink_hole(hole);
+        // Slots never drop their contents, so the copy left behind is popped as a hole
+        let slot = ptr::read(self.storage.get_unchecked(last));
+        *self.storage.get_unchecked_mut(hole) = slot;
+        self.occupancy.set(hole, true);
+        self.storage.pop();
+        self.occupancy.resize(last);
+        self.truncate_link_table(last);
+        last
+    }
+    /// Returns a reference to the element at the specified index.
+    ///
+    /// # Safety
+    /// The slot at the specified index must contain an element.
+    unsafe fn element_unchecked(&self, index: usize) -> &E {
+        self.storage.get_unchecked(index).element()
+    }
+    /// Returns a mutable reference to the element at the specified index.
+    ///
+    /// # Safety
+    /// The slot at the specified index must contain an element.
+    unsafe fn element_unchecked_mut(&mut self, index: usize) -> &mut E {
+        self.storage.get_unchecked_mut(index).element_mut()
+    }
+    /// Panics if the specified index is a hole or out of bounds.
+    #[track_caller]
+    fn assert_element(&self, index: usize) {
+        assert!(self.len() > index, "index out of bounds");
+        assert!(self.occupancy.get(index), "{}", HOLE_PANIC_MSG);
+    }
+    /// Sets the policy which decides when the storage defragments itself automatically. See [`DefragPolicy`] for more.
+    ///
+    /// [`DefragPolicy`]: enum.DefragPolicy.html " "
+    pub fn set_defrag_policy(&mut self, policy: DefragPolicy) {
+        self.defrag_policy = policy;
+    }
+    /// Returns the policy which decides when the storage defragments itself automatically.
+    pub const fn defrag_policy(&self) -> DefragPolicy {
+        self.defrag_policy
+    }
+    /// The hole reuse strategy which the storage starts with: `Fifo` if the links of the hole list fit into the holes, `LowestIndex` otherwise, so that small elements don't need the link table.
+    const DEFAULT_HOLE_REUSE: HoleReuse = if Slot::<E>::HAS_LINKS {
+        HoleReuse::Fifo
+    } else {
+        HoleReuse::LowestIndex
+    };
+    /// Sets the strategy which decides which hole is filled when an element is added. See [`HoleReuse`] for more.
+    ///
+    /// If the elements are smaller than two pointers, switching to `Fifo` or `Lifo` for the first time starts keeping the hole list in a table of two words per slot. The holes which exist at that point are linked in the order of their indices, since the order in which they were punched was never recorded.
+    ///
+    /// [`HoleReuse`]: enum.HoleReuse.html " "
+    pub fn set_hole_reuse(&mut self, strategy: HoleReuse) {
+        if strategy != HoleReuse::LowestIndex && !self.linked {
+            self.linked = true;
+            self.relink_holes();
+        }
+        self.hole_reuse = strategy;
+    }
+    /// Returns the strategy which decides which hole is filled when an element is added.
+    pub const fn hole_reuse(&self) -> HoleReuse {
+        self.hole_reuse
+    }
+    /// Rebuilds the hole list from the occupancy bitmap, linking the holes starting from the specified index in the order of their indices. The holes before it must already be in the hole list, and the ones after it must not.
+    fn relink_holes_from(&mut self, from: usize) {
+        let mut front = from;
+        while let Some(hole) = self.occupancy.next(false, front, self.len()) {
+            self.extend_link_table(hole);
+            unsafe {
+                // SAFETY: the bitmap only has bits for slots within bounds, and the hole isn't in
+                // the hole list yet as per the contract
+                self.link_hole(hole);
+            }
+            front = hole + 1;
+        }
+    }
+    /// Rebuilds the entire hole list from the occupancy bitmap, linking the holes in the order of their indices.
+    fn relink_holes(&mut self) {
+        self.hole_list = None;
+        self.relink_holes_from(0);
+    }
+    /// Defragments the storage or pops off holes from its end if the defragmentation policy says so. Called after a hole is punched.
+    fn apply_defrag_policy(&mut self)
+    where
+        E: MoveFix,
+    {
+        match self.defrag_policy {
+            DefragPolicy::Never => {}
+            DefragPolicy::HoleRatio { .. } => {
+                if self
+                    .defrag_policy
+                    .exceeds_hole_ratio(self.num_holes(), self.len())
+                {
+                    self.defragment_and_fix();
+                }
+            }
+            DefragPolicy::TrailingHoles(max) => {
+                // Skips the trailing holes a word at a time, so this is cheap even if there are
+                // lots of them
+                let len = self.len();
+                let num_trailing = len - self.occupancy.prev(true, 0, len).map_or(0, |i| i + 1);
+                if num_trailing > max {
+                    self.pop_trailing_holes();
+                }
+            }
+        }
+    }
+    /// Returns a view of the slot at the specified index, or `None` if the index is out of bounds. Unlike `get`, this never panics on holes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, SparseVec, SparseStorageSlotRef as SlotRef, DummyMoveFix};
+    ///
+    /// let mut storage = SparseVec::<DummyMoveFix<u32>>::new();
+    /// for value in 0..3 {
+    ///     storage.add(value.into());
+    /// }
+    /// storage.remove_and_shiftfix(1);
+    ///
+    /// assert_eq!(storage.slot(0), Some(SlotRef::Element(&0.into())));
+    /// assert_eq!(storage.slot(1), Some(SlotRef::Hole { prev: None, next: None }));
+    /// assert_eq!(storage.slot(3), None);
+    /// assert!(storage.is_hole(1));
+    /// assert_eq!(storage.try_get(1), None);
+    /// assert_eq!(storage.try_get(2), Some(&2.into()));
+    /// ```
+    pub fn slot(&self, index: usize) -> Option<SlotRef<'_, E>> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(if self.occupancy.get(index) {
+            SlotRef::Element(unsafe {
+                // SAFETY: the occupancy bit is set
+                self.element_unchecked(index)
+            })
+        } else {
+            let links = unsafe {
+                // SAFETY: we just did bounds checking, and the slot is a hole
+                self.hole_links(index)
+            };
+            SlotRef::Hole {
+                prev: links.prev(),
+                next: links.next(),
+            }
+        })
+    }
+    /// Returns `true` if the slot at the specified index is a hole, `false` if it contains an element or the index is out of bounds.
+    pub fn is_hole(&self, index: usize) -> bool {
+        index < self.len() && !self.occupancy.get(index)
+    }
+    /// Returns a reference to the element at the specified index, or `None` if there's a hole there or the index is out of bounds. Unlike `get`, this never panics on holes.
+    pub fn try_get(&self, index: usize) -> Option<&E> {
+        if self.occupancy.get(index) {
+            Some(unsafe {
+                // SAFETY: bits past the end are always clear
+                self.element_unchecked(index)
+            })
+        } else {
+            None
+        }
+    }
+    /// Returns a mutable reference to the element at the specified index, or `None` if there's a hole there or the index is out of bounds. Unlike `get_mut`, this never panics on holes.
+    pub fn try_get_mut(&mut self, index: usize) -> Option<&mut E> {
+        if self.occupancy.get(index) {
+            Some(unsafe {
+                // SAFETY: as above
+                self.element_unchecked_mut(index)
+            })
+        } else {
+            None
+        }
+    }
+    /// Consumes the sparse storage and returns its inner storage.
+    ///
+    /// The slots don't record whether they contain an element, so the elements which are left in the returned storage are never dropped. Call `truncate(0)` first if they need to be.
+    pub fn into_inner(self) -> S {
+        let this = mem::ManuallyDrop::new(self);
+        unsafe {
+            // SAFETY: the sparse storage is never dropped, so the storages are moved out of it
+            // exactly once, and the rest of the fields are plain data
+            drop(ptr::read(&this.occupancy));
+            drop(ptr::read(&this.link_table));
+            ptr::read(&this.storage)
+        }
+    }
+    /// Returns the number of holes in the storage. This operation returns immediately instead of looping through the entire storage, since the sparse storage automatically tracks the number of holes it creates and destroys.
+    pub fn num_holes(&self) -> usize {
+        self.hole_list.map_or(0, |x| x.0.get())
+    }
+    /// Returns `true` if there are no holes in the storage, `false` otherwise. This operation returns immediately instead of looping through the entire storage, since the sparse storage automatically tracks the number of holes it creates and destroys.
+    pub fn is_dense(&self) -> bool {
+        self.num_holes() == 0
+    }
+    /// Replaces the element at the specified index with a hole, returning it, or `None` if the index is out of bounds or there's already a hole there. Unlike `remove_and_shiftfix`, this never panics, doesn't require `MoveFix` and never applies the defragmentation policy, so the indices of all other elements are left untouched.
+    ///
+    /// # Example
+    /// ```rust
+    /// use granite::{ListStorage, SparseVec};
+    ///
+    /// let mut storage = SparseVec::<u32>::new();
+    /// storage.push(0);
+    /// storage.push(1);
+    /// assert_eq!(storage.take(0), Some(0));
+    /// assert_eq!(storage.take(0), None);
+    /// assert_eq!(storage.take(2), None);
+    /// assert!(storage.is_hole(0));
+    /// assert_eq!(storage.try_get(1), Some(&1));
+    /// ```
+    pub fn take(&mut self, index: usize) -> Option<E> {
+        if index >= self.len() {
+            return None;
+        }
+        unsafe {
+            // SAFETY: we just did bounds checking, and hole info always points to holes
+            self.punch_hole(index)
+        }
+    }
+
+    /// Turns off the hole list of an empty storage, so that holes are never written to and are always filled lowest index first. Used by `BitSparseStorage`.
+    pub(crate) fn into_unlinked(mut self) -> Self {
+        debug_assert!(self.is_empty(), "holes can only be unlinked before there are any");
+        self.linked = false;
+        self.hole_reuse = HoleReuse::LowestIndex;
+        self
+    }
+
+    /// Sets the specified element to a hole, returning the value or `None` if it was already a hole.
+    ///
+    /// # Safety
+    /// The specified index must be within range. Hole info must not point to non-holes.
+    unsafe fn punch_hole(&mut self, index: usize) -> Option<E> {
+        if !self.occupancy.get(index) {
+            return None;
+        }
+        // Done first so that the storage stays intact if a fixed-capacity link table is full
+        self.extend_link_table(index);
+        self.occupancy.set(index, false);
+        let element = /*unsafe*/ {
+            // SAFETY: the slot contained an element, and it's now marked as a hole so that it
+            // doesn't get read again
+            ptr::read(self.element_unchecked(index))
+        };
+        /*unsafe*/
+        {
+            // SAFETY: we just punched an unlinked hole at an index which is within range
+            self.link_hole(index);
+        }
+        Some(element)
+    }
+    /// Returns the index of the hole which will be filled by the next element to be added according to the hole reuse strategy, or `None` if there are no holes.
+    fn next_hole(&self) -> Option<usize> {
+        let (_, head, tail) = self.hole_list?;
+        match self.hole_reuse {
+            HoleReuse::Fifo if self.linked => Some(head),
+            HoleReuse::Lifo if self.linked => Some(tail),
+            _ => self.occupancy.first_clear(self.len()),
+        }
+    }
+    /// Appends the hole at the specified index to the end of the hole list, which keeps the holes in the order in which they were punched.
+    ///
+    /// # Safety
+    /// The specified index must be within range and point to a hole which is not in the hole list. Hole info must not point to non-holes.
+    unsafe fn link_hole(&mut self, index: usize) {
+        let linked = self.linked;
+        let prev = if let Some(hole_info) = &mut self.hole_list {
+            hole_info.0 = /*unsafe*/ {
+                // SAFETY: it's impossible to have more than usize::MAX elements in a Storage
+                NonZeroUsize::new_unchecked(hole_info.0.get() + 1)
+            };
+            if !linked {
+                // Unlinked holes are only counted, and the head and tail of the list are
+                // meaningless
+                return;
+            }
+            let old_tail = hole_info.2;
+            // Set end to the hole being linked
+            hole_info.2 = index;
+            Some(old_tail)
+        } else {
+            self.hole_list = Some((
+                /*unsafe*/
+                {
+                    // SAFETY: self explanatory
+                    NonZeroUsize::new_unchecked(1)
+                }, // Only one hole
+                index, // List starts from the new hole...
+                index, // ...and ends with it
+            ));
+            None
+        };
+        if let Some(old_tail) = prev {
+            // SAFETY: hole info always points to holes within bounds
+            self.update_hole_links(old_tail, |links| links.set_next(Some(index)));
+        }
+        let mut links = HoleLinks::UNLINKED;
+        links.set_prev(prev);
+        /*unsafe*/
+        {
+            // SAFETY: see safety contract
+            self.set_hole_links(index, links);
+        }
+    }
+    /// Removes the hole at the specified index from the hole list, leaving it as a hole which is not linked to any other ones.
+    ///
+    /// # Safety
+    /// The specified index must be within range and point to a hole which is in the hole list. Hole info must not point to non-holes.
+    #[allow(clippy::option_if_let_else)] // I hate map_or_else
+    unsafe fn unlink_hole(&mut self, index: usize) {
+        let links = /*unsafe*/ {
+            // SAFETY: see safety contract
+            self.hole_links(index)
+        };
+        let mut hole_info = match self.hole_list {
+            Some(x) => x,
+            // SAFETY: the hole is in the hole list, so the list cannot be empty
+            None => hint::unreachable_unchecked(),
+        };
+        if let Some(new_hole_count) = NonZeroUsize::new(hole_info.0.get() - 1) {
+            hole_info.0 = new_hole_count;
+            self.hole_list = Some(hole_info);
+            if !self.linked {
+                // As in link_hole
+                return;
+            }
+            // SAFETY of all unreachable_unchecked calls below: according to hole count, the hole
+            // list doesn't consist of this one hole alone, so it must have at least one neighbour
+            // SAFETY of all update_hole_links calls below: hole links always point to holes within
+            // bounds
+            match links.prev() {
+                Some(prev) => {
+                    self.update_hole_links(prev, |prev_links| prev_links.set_next(links.next()));
+                }
+                None => {
+                    hole_info.1 = links.next().unwrap_or_else(|| hint::unreachable_unchecked());
+                }
+            }
+            match links.next() {
+                Some(next) => {
+                    self.update_hole_links(next, |next_links| next_links.set_prev(links.prev()));
+                }
+                None => {
+                    hole_info.2 = links.prev().unwrap_or_else(|| hint::unreachable_unchecked());
+                }
+            }
+            self.hole_list = Some(hole_info);
+        } else {
+            self.hole_list = None;
+        }
+        self.set_hole_links(index, HoleLinks::UNLINKED);
+    }
+    /// Updates the indices stored in the hole list after the slots were shifted around. Takes time proportional to the number of holes.
+    ///
+    /// # Safety
+    /// `renumber` must map the old index of every hole in the hole list to its current index, and no hole may have been added or removed while shifting.
+    unsafe fn renumber_holes(&mut self, renumber: impl Fn(usize) -> usize) {
+        if !self.linked {
+            return;
+        }
+        let hole_info = match &mut self.hole_list {
+            Some(x) => x,
+            None => return,
+        };
+        hole_info.1 = renumber(hole_info.1);
+        hole_info.2 = renumber(hole_info.2);
+        let mut current = Some(hole_info.1);
+        while let Some(index) = current {
+            // SAFETY: see safety contract
+            let mut links = /*unsafe*/ { self.hole_links(index) };
+            let next = links.next().map(&renumber);
+            links.set_prev(links.prev().map(&renumber));
+            links.set_next(next);
+            /*unsafe*/
+            {
+                // SAFETY: as above
+                self.set_hole_links(index, links);
+            }
+            current = next;
+        }
+    }
+    /// Returns `true` if the links of the holes are kept in the link table instead of the holes themselves.
+    fn uses_link_table(&self) -> bool {
+        self.linked && !Slot::<E>::HAS_LINKS
+    }
+    /// Makes sure that the link table covers the slot at the specified index if it's used at all.
+    fn extend_link_table(&mut self, index: usize) {
+        if self.uses_link_table() {
+            while self.link_table.len() < (index + 1) * 2 {
+                self.link_table.push(HoleLinks::NONE);
+            }
+        }
+    }
+    /// Shifts the links in the link table after a slot was inserted at the specified index.
+    fn insert_table_links(&mut self, index: usize) {
+        if self.link_table.len() > index * 2 {
+            self.link_table.insert(index * 2, HoleLinks::NONE);
+            self.link_table.insert(index * 2, HoleLinks::NONE);
+        }
+    }
+    /// Shifts the links in the link table after a slot was removed from the specified index.
+    fn remove_table_links(&mut self, index: usize) {
+        if self.link_table.len() > index * 2 {
+            self.link_table.remove(index * 2);
+            self.link_table.remove(index * 2);
+        }
+    }
+    /// Cuts the link table off after the specified amount of slots.
+    fn truncate_link_table(&mut self, len: usize) {
+        if self.link_table.len() > len * 2 {
+            self.link_table.truncate(len * 2);
+        }
+    }
+    /// Returns the links of the hole at the specified index, or unlinked ones if the holes aren't linked.
+    ///
+    /// # Safety
+    /// The specified index must be within range and point to a hole which had its links set.
+    unsafe fn hole_links(&self, index: usize) -> HoleLinks {
+        if !self.linked {
+            HoleLinks::UNLINKED
+        } else if Slot::<E>::HAS_LINKS {
+            self.storage.get_unchecked(index).hole_links()
+        } else {
+            HoleLinks {
+                prev: *self.link_table.get_unchecked(index * 2),
+                next: *self.link_table.get_unchecked(index * 2 + 1),
+            }
+        }
+    }
+    /// Stores the links of the hole at the specified index, or does nothing if the holes aren't linked.
+    ///
+    /// # Safety
+    /// The specified index must be within range and point to a hole, and the link table must cover it if it's used.
+    unsafe fn set_hole_links(&mut self, index: usize, links: HoleLinks) {
+        if !self.linked {
+            return;
+        }
+        if Slot::<E>::HAS_LINKS {
+            self.storage.get_unchecked_mut(index).set_hole_links(links);
+        } else {
+            *self.link_table.get_unchecked_mut(index * 2) = links.prev;
+            *self.link_table.get_unchecked_mut(index * 2 + 1) = links.next;
+        }
+    }
+    /// # Safety
+    /// Same as `set_hole_links`.
+    unsafe fn update_hole_links(&mut self, index: usize, f: impl FnOnce(&mut HoleLinks)) {
+        let mut links = self.hole_links(index);
+        f(&mut links);
+        self.set_hole_links(index, links);
+    }
+    /// Pops all holes off the end of the storage, removing them from the hole list.
+    fn pop_trailing_holes(&mut self) {
+        while self.len() > 0 && !self.occupancy.get(self.len() - 1) {
+            self.pop_slot();
+        }
+    }
+    /// Removes the last slot, removing it from the hole list if it's a hole, and returns its element if it has one.
+    fn pop_slot(&mut self) -> Option<E> {
+        let last = self.len().checked_sub(1)?;
+        let occupied = self.occupancy.get(last);
+        if !occupied {
+            unsafe {
+                // SAFETY: the index is the last one, and every hole in the storage is in the hole
+                // list
+                self.unlink_hole(last);
+            }
+        }
+        let slot = self.storage.pop()?;
+        self.occupancy.resize(last);
+        self.truncate_link_table(last);
+        if occupied {
+            Some(unsafe {
+                // SAFETY: the occupancy bit was set
+                slot.into_element()
+            })
+        } else {
+            None
+        }
+    }
+}
+static HOLE_PANIC_MSG: &str = "\
+the element at the specified index was a hole in the sparse storage";
+unsafe impl<E, S, B> ListStorage for SparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    type Element = E;
+    const CAPACITY: Option<usize> = S::CAPACITY;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            storage: S::with_capacity(capacity),
+            occupancy: Bitmap::with_capacity(capacity),
+            hole_list: None,
+            link_table: B::new(),
+            // Small elements start off without the link table, see `set_hole_reuse`
+            linked: Slot::<E>::HAS_LINKS,
+            defrag_policy: DefragPolicy::Never,
+            hole_reuse: Self::DEFAULT_HOLE_REUSE,
+        }
+    }
+    fn insert(&mut self, index: usize, element: Self::Element) {
+        let len = self.len();
+        // Normal inserts don't fill holes, but the ones after the index are shifted
+        self.storage.insert(index, Slot::new_element(element));
+        self.occupancy.insert(index, true, len);
+        self.insert_table_links(index);
+        unsafe {
+            // SAFETY: every slot starting from the index was shifted to the right by one
+            self.renumber_holes(|i| if i >= index { i + 1 } else { i });
+        }
+    }
+    // Will panic if a hole is encountered at the index.
+    #[track_caller]
+    fn remove(&mut self, index: usize) -> Self::Element {
+        self.assert_element(index);
+        let len = self.len();
+        let element = unsafe {
+            // SAFETY: we checked the occupancy bit
+            self.storage.remove(index).into_element()
+        };
+        self.occupancy.remove(index, len);
+        self.remove_table_links(index);
+        unsafe {
+            // SAFETY: every slot after the index was shifted to the left by one, and the removed
+            // slot wasn't a hole
+            self.renumber_holes(|i| if i > index { i - 1 } else { i });
+        }
+        element
+    }
+    fn len(&self) -> usize {
+        self.storage.len()
+    }
+    // Will panic if a hole is encountered at the index.
+    unsafe fn get_unchecked(&self, index: usize) -> &Self::Element {
+        assert!(self.occupancy.get(index), "{}", HOLE_PANIC_MSG);
+        self.element_unchecked(index)
+    }
+    // Will panic if a hole is encountered at the index.
+    unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut Self::Element {
+        assert!(self.occupancy.get(index), "{}", HOLE_PANIC_MSG);
+        self.element_unchecked_mut(index)
+    }
+
+    fn contains_index(&self, index: usize) -> bool {
+        self.occupancy.get(index)
+    }
+
+    // Will panic if a hole is encountered at the index.
+    #[track_caller]
+    fn get(&self, index: usize) -> Option<&Self::Element> {
+        if index < self.len() {
+            self.assert_element(index);
+            Some(unsafe {
+                // SAFETY: we just checked the occupancy bit
+                self.element_unchecked(index)
+            })
+        } else {
+            None
+        }
+    }
+    // Will panic if a hole is encountered at the index.
+    #[track_caller]
+    fn get_mut(&mut self, index: usize) -> Option<&mut Self::Element> {
+        if index < self.len() {
+            self.assert_element(index);
+            Some(unsafe {
+                // SAFETY: as above
+                self.element_unchecked_mut(index)
+            })
+        } else {
+            None
+        }
+    }
+    fn new() -> Self {
+        Self {
+            storage: S::new(),
+            occupancy: Bitmap::new(),
+            hole_list: None,
+            link_table: B::new(),
+            // Small elements start off without the link table, see `set_hole_reuse`
+            linked: Slot::<E>::HAS_LINKS,
+            defrag_policy: DefragPolicy::Never,
+            hole_reuse: Self::DEFAULT_HOLE_REUSE,
+        }
+    }
+    fn push(&mut self, element: Self::Element) {
+        let len = self.len();
+        self.storage.push(Slot::new_element(element));
+        self.occupancy.resize(len + 1);
+        self.occupancy.set(len, true);
+    }
+    // Discards the holes at the end of the storage to get to the last element.
+    fn pop(&mut self) -> Option<Self::Element> {
+        self.pop_trailing_holes();
+        self.pop_slot()
+    }
+    fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+    fn reserve(&mut self, additional: usize) {
+        self.storage.reserve(additional);
+        self.occupancy.reserve(self.len() + additional);
+    }
+    fn shrink_to_fit(&mut self) {
+        self.storage.shrink_to_fit();
+        self.occupancy.shrink_to_fit();
+        if self.is_dense() {
+            // Nothing is linked, so the table can be extended again when needed
+            self.link_table.truncate(0);
+        }
+        self.link_table.shrink_to_fit();
+    }
+    fn truncate(&mut self, len: usize) {
+        // Slots are popped one by one so that the storage stays consistent if a destructor panics
+        while self.len() > len {
+            drop(self.pop_slot());
+        }
+    }
+    /// Moves the holes along with the elements. The holes which end up in the new storage are linked into its hole list in the order of their indices, and the new storage has the same settings as this one.
+    #[track_caller]
+    fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(at <= len, "index out of bounds");
+        // The holes which are moved out have to leave the hole list while they're still here
+        let mut front = at;
+        while let Some(hole) = self.occupancy.next(false, front, len) {
+            unsafe {
+                // SAFETY: the bitmap only has bits for slots within bounds, and every hole is in
+                // the hole list
+                self.unlink_hole(hole);
+            }
+            front = hole + 1;
+        }
+        // Slots never drop their contents, so they can be moved without knowing what's in them
+        let mut tail = Self {
+            storage: self.storage.split_off(at),
+            occupancy: Bitmap::with_capacity(len - at),
+            hole_list: None,
+            link_table: B::new(),
+            linked: self.linked,
+            defrag_policy: self.defrag_policy,
+            hole_reuse: self.hole_reuse,
+        };
+        tail.occupancy.resize(len - at);
+        let mut front = at;
+        while let Some(index) = self.occupancy.next(true, front, len) {
+            tail.occupancy.set(index - at, true);
+            front = index + 1;
+        }
+        self.occupancy.resize(at);
+        self.truncate_link_table(at);
+        tail.relink_holes();
+        tail
+    }
+    /// Moves the holes along with the elements. The holes of `other` are linked into the hole list after the ones of this storage, in the order of their indices.
+    fn append(&mut self, other: &mut Self) {
+        let len = self.len();
+        let other_len = other.len();
+        self.storage.append(&mut other.storage);
+        self.occupancy.resize(len + other_len);
+        let mut front = 0;
+        while let Some(index) = other.occupancy.next(true, front, other_len) {
+            self.occupancy.set(len + index, true);
+            front = index + 1;
+        }
+        self.relink_holes_from(len);
+        other.occupancy.resize(0);
+        other.hole_list = None;
+        other.link_table.truncate(0);
+    }
+    fn insert_and_shiftfix(&mut self, index: usize, element: Self::Element)
+    where
+        Self::Element: MoveFix,
+    {
+        // We are not filling holes here, the elements after the index are shifted just like they
+        // would be without sparse storage
+        self.insert(index, element);
+        unsafe {
+            // SAFETY: we are indeed shifting
+            Self::Element::fix_right_shift(self, index, super::U_ONE);
+        }
+    }
+    #[track_caller]
+    fn remove_and_shiftfix(&mut self, index: usize) -> Self::Element
+    where
+        Self::Element: MoveFix,
+    {
+        assert!(self.len() > index, "index out of bounds");
+        let element = unsafe {
+            // SAFETY: we just did bounds checking
+            self.punch_hole(index)
+        }
+        .expect(HOLE_PANIC_MSG);
+        self.apply_defrag_policy();
+        element
+    }
+    #[allow(clippy::option_if_let_else)] // I hate map_or_else
+    fn add(&mut self, element: Self::Element) -> usize {
+        if let Some(used_hole_index) = self.next_hole() {
+            unsafe {
+                // SAFETY: hole info and the occupancy bitmap always point to holes within bounds
+                self.unlink_hole(used_hole_index);
+                *self.storage.get_unchecked_mut(used_hole_index) = Slot::new_element(element);
+            }
+            self.occupancy.set(used_hole_index, true);
+            used_hole_index
+        } else {
+            self.push(element);
+            self.len() - 1
+        }
+    }
+    fn try_insert(
+        &mut self,
+        index: usize,
+        element: Self::Element,
+    ) -> Result<(), CapacityError<Self::Element>> {
+        let len = self.len();
+        self.storage
+            .try_insert(index, Slot::new_element(element))
+            .map_err(|e| {
+                e.map(|slot| unsafe {
+                    // SAFETY: we just created the slot from an element
+                    slot.into_element()
+                })
+            })?;
+        self.occupancy.insert(index, true, len);
+        self.insert_table_links(index);
+        unsafe {
+            // SAFETY: as in insert
+            self.renumber_holes(|i| if i >= index { i + 1 } else { i });
+        }
+        Ok(())
+    }
+    fn try_push(&mut self, element: Self::Element) -> Result<(), CapacityError<Self::Element>> {
+        let len = self.len();
+        self.storage
+            .try_push(Slot::new_element(element))
+            .map_err(|e| {
+                e.map(|slot| unsafe {
+                    // SAFETY: we just created the slot from an element
+                    slot.into_element()
+                })
+            })?;
+        self.occupancy.resize(len + 1);
+        self.occupancy.set(len, true);
+        Ok(())
+    }
+    fn try_add(&mut self, element: Self::Element) -> Result<usize, CapacityError<Self::Element>> {
+        if self.is_dense() {
+            self.try_push(element)?;
+            Ok(self.len() - 1)
+        } else {
+            // Filling a hole never requires additional capacity
+            Ok(self.add(element))
+        }
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        self.storage.try_reserve(additional)?;
+        self.occupancy.reserve(self.len() + additional);
+        Ok(())
+    }
+}
+impl<E, S, B> Drop for SparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn drop(&mut self) {
+        self.truncate(0);
+    }
+}
+impl<E, S, B> Clone for SparseStorage<E, S, B>
+where
+    E: Clone,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize> + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut storage = S::with_capacity(self.len());
+        for index in 0..self.len() {
+            let slot = self.try_get(index).map_or_else(
+                || unsafe {
+                    // SAFETY: the index is within bounds, and holes contain nothing but plain
+                    // integers, if anything at all
+                    ptr::read(self.storage.get_unchecked(index))
+                },
+                |element| Slot::new_element(element.clone()),
+            );
+            storage.push(slot);
+        }
+        Self {
+            storage,
+            occupancy: self.occupancy.clone(),
+            hole_list: self.hole_list,
+            link_table: self.link_table.clone(),
+            linked: self.linked,
+            defrag_policy: self.defrag_policy,
+            hole_reuse: self.hole_reuse,
+        }
+    }
+}
+impl<E, S, B> Debug for SparseStorage<E, S, B>
+where
+    E: Debug,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len()).map(|index| self.try_get(index)))
+            .finish()
+    }
+}
+impl<E, S, B> Default for SparseStorage<E, S, B>
+where
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E, S, B> PartialEq for SparseStorage<E, S, B>
+where
+    E: PartialEq,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.num_holes() == other.num_holes()
+            && (0..self.len()).all(|index| self.try_get(index) == other.try_get(index))
+    }
+}
+impl<E, S, B> Eq for SparseStorage<E, S, B>
+where
+    E: Eq,
+    S: ListStorage<Element = Slot<E>>,
+    B: ListStorage<Element = usize>,
+{
+}
+impl<'a, E, S, B> IntoRefIterator<'a> for SparseStorage<E, S, B>
+where
+    E: 'a,
+    S: DenseList<Element = Slot<E>> + IntoRefIterator<'a, Item = Slot<E>>,
+    B: ListStorage<Element = usize> + 'a,
+{
+    type Item = E;
+    type Iter = Iter<'a, E, <S as IntoRefIterator<'a>>::Iter, B>;
+    /// Returns an iterator over references to the elements which skips holes. The index of each element can be retrieved by using the `indexed` method on the iterator.
+    fn iter(&'a self) -> Self::Iter {
+        let num_elements = self.len() - self.num_holes();
+        Iter::new(self.storage.iter(), &self.occupancy, self.len(), num_elements)
+    }
+}
+impl<'a, E, S, B> IntoMutIterator<'a> for SparseStorage<E, S, B>
+where
+    E: 'a,
+    S: DenseList<Element = Slot<E>> + IntoMutIterator<'a, Item = Slot<E>>,
+    B: ListStorage<Element = usize> + 'a,
+{
+    type Item = E;
+    type IterMut = IterMut<'a, E, <S as IntoMutIterator<'a>>::IterMut, B>;
+    /// Returns an iterator over mutable references to the elements which skips holes. The index of each element can be retrieved by using the `indexed` method on the iterator.
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        let len = self.len();
+        let num_elements = len - self.num_holes();
+        IterMut::new(self.storage.iter_mut(), &self.occupancy, len, num_elements)
+    }
+}
+impl<'a, E, S, B> StorageIter<'a> for SparseStorage<E, S, B>
+where
+    E: MoveFix + 'a,
+    S: List<Element = Slot<E>> + DenseList + 'a,
+    B: ListStorage<Element = usize> + 'a,
+{
+    type Keys = Map<Indexed<Self::Values>, fn((usize, &'a E)) -> usize>;
+    type Values = Iter<'a, E, <S as IntoRefIterator<'a>>::Iter, B>;
+    type ValuesMut = IterMut<'a, E, <S as IntoMutIterator<'a>>::IterMut, B>;
+    type Iter = Indexed<Self::Values>;
+    type IterMut = Indexed<Self::ValuesMut>;
+
+    fn keys(&'a self) -> Self::Keys {
+        StorageIter::iter(self).map(|(index, _)| index)
+    }
+    fn values(&'a self) -> Self::Values {
+        IntoRefIterator::iter(self)
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        IntoMutIterator::iter_mut(self)
+    }
+    fn iter(&'a self) -> Self::Iter {
+        IntoRefIterator::iter(self).indexed()
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        IntoMutIterator::iter_mut(self).indexed()
+    }
+}
+#[cfg(feature = "rayon")]
+impl<'a, E, S, B> IntoParRefIterator<'a> for SparseStorage<E, S, B>
+where
+    E: Sync + 'a,
+    S: DenseList<Element = Slot<E>> + IntoParRefIterator<'a, Item = Slot<E>>,
+    <S as IntoParRefIterator<'a>>::ParIter: IndexedParallelIterator,
+    B: ListStorage<Element = usize> + Sync + 'a,
+{
+    type Item = E;
+    type ParIter = ParIter<'a, E, <S as IntoParRefIterator<'a>>::ParIter, B>;
+    /// Returns a parallel iterator over references to the elements which skips holes.
+    ///
+    /// The inner storage has to be a [`DenseList`] with an indexed parallel iterator so that the holes can be told apart from the elements, which is the case for `Vec` and `VecDeque`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rayon::iter::ParallelIterator;
+    /// use granite::{ListStorage, IntoParRefIterator, IntoParMutIterator, SparseVec, DummyMoveFix};
+    ///
+    /// let mut storage = SparseVec::<DummyMoveFix<u64>>::new();
+    /// for value in 0..1000 {
+    ///     storage.add(value.into());
+    /// }
+    /// for index in (0..1000).step_by(2) {
+    ///     storage.remove_and_shiftfix(index);
+    /// }
+    /// storage.par_elements_mut().for_each(|value| value.0 *= 2);
+    /// let sum: u64 = storage.par_elements().map(|value| value.0).sum();
+    /// assert_eq!(sum, (1..1000).step_by(2).map(|value| value * 2).sum());
+    /// ```
+    ///
+    /// [`DenseList`]: trait.DenseList.html " "
+    fn par_elements(&'a self) -> Self::ParIter {
+        ParIter::new(self.storage.par_elements(), &self.occupancy)
+    }
+}
+#[cfg(feature = "rayon")]
+impl<'a, E, S, B> IntoParMutIterator<'a> for SparseStorage<E, S, B>
+where
+    E: Send + 'a,
+    S: DenseList<Element = Slot<E>> + IntoParMutIterator<'a, Item = Slot<E>>,
+    <S as IntoParMutIterator<'a>>::ParIterMut: IndexedParallelIterator,
+    B: ListStorage<Element = usize> + Sync + 'a,
+{
+    type Item = E;
+    type ParIterMut = ParIterMut<'a, E, <S as IntoParMutIterator<'a>>::ParIterMut, B>;
+    /// Returns a parallel iterator over mutable references to the elements which skips holes.
+    fn par_elements_mut(&'a mut self) -> Self::ParIterMut {
+        ParIterMut::new(self.storage.par_elements_mut(), &self.occupancy)
+    }
+}
+
+/// A slot inside a sparse storage.
+///
+/// This is an opaque structure, only used for the purpose of a `SparseStorage` being validly declarable, because leaking private types through generic argument defaults is impossible, and it'd be impossible to declare the type of the backing storage if it was explicitly different. Slots don't know whether they contain an element, since that is recorded in the occupancy bitmap of the [`SparseStorage`] which owns them, so they can only be inspected through the storage, by index.
+///
+/// # Accessing slots
+/// Slots used to have accessor methods of their own, which can't work without the occupancy bitmap. Each of them has a replacement on the sparse storage which takes the index of the slot instead:
+/// - `is_element` — [`ListStorage::contains_index`];
+/// - `is_hole` — [`SparseStorage::is_hole`];
+/// - `get` and `get_mut` — [`SparseStorage::try_get`] and [`SparseStorage::try_get_mut`];
+/// - `into_element` — [`SparseStorage::take`], which leaves a hole behind;
+/// - `as_slot_ref` — [`SparseStorage::slot`], which returns the same [`SlotRef`] view.
+/// ```rust
+/// use granite::{ListStorage, SparseVec, SparseStorageSlotRef as SlotRef};
+///
+/// let mut storage = SparseVec::<u32>::new();
+/// storage.push(7);
+/// storage.push(8);
+/// storage.take(0);
+/// assert!(storage.is_hole(0));
+/// assert!(storage.contains_index(1));
+/// assert_eq!(storage.slot(1), Some(SlotRef::Element(&8)));
+/// *storage.try_get_mut(1).unwrap() += 1;
+/// assert_eq!(storage.take(1), Some(9));
+/// ```
+///
+/// # Size and representation
+/// *The contents of this section are an implementation detail. Unless stated otherwise, relying on those for memory safety may cause undefined behavior.*
+///
+/// The structure is a `repr(transparent)` wrapper around `MaybeUninit<T>`, so it has exactly the same size and alignment as `T`. The layout of holes is chosen automatically depending on the size of `T`:
+/// - if `T` is at least two pointers big, a hole stores the indices of the previous and next holes in the hole list in the bytes which the element used to occupy;
+/// - otherwise, a hole stores nothing. The storage fills the lowest hole by default, which it finds through the occupancy bitmap alone. Only if the `Fifo` or `Lifo` [hole reuse strategy] is selected, the indices are stored in a table of two words per slot next to the occupancy bitmap, which grows as far as the last slot which was turned into a hole.
+///
+/// Either way, the slots themselves have no overhead, and unless the link table is used, the storage only adds the bit in the occupancy bitmap, plus a bit in its summary level for every word of bits. This is smaller than the enum-based layout which was used before, which needed room for a discriminant and a link in every slot:
+/// ```rust
+/// use core::mem::size_of;
+/// use granite::SparseStorageSlot as Slot;
+///
+/// // The previous layout of slots
+/// enum SlotEnumBased<T> {
+///     Element(T),
+///     Hole(Option<usize>),
+/// }
+///
+/// assert_eq!(size_of::<Slot<u32>>(), size_of::<u32>());
+/// assert_eq!(size_of::<Slot<[usize; 4]>>(), size_of::<[usize; 4]>());
+/// assert!(size_of::<Slot<u32>>() < size_of::<SlotEnumBased<u32>>());
+/// assert!(size_of::<Slot<u64>>() < size_of::<SlotEnumBased<u64>>());
+/// assert!(size_of::<Slot<[usize; 4]>>() < size_of::<SlotEnumBased<[usize; 4]>>());
+/// ```
+///
+/// # Dropping
+/// Slots never drop their contents, since they can't know whether they have any. The storage drops the elements exactly once: when they are removed from it or when it's dropped itself. Holes never drop anything.
+/// ```rust
+/// use std::rc::Rc;
+/// use granite::{ListStorage, SparseVec, DummyMoveFix};
+///
+/// let counter = Rc::new(());
+/// let mut storage = SparseVec::<DummyMoveFix<Rc<()>>>::new();
+/// for _ in 0..8 {
+///     storage.add(Rc::clone(&counter).into());
+/// }
+/// assert_eq!(Rc::strong_count(&counter), 9);
+/// drop(storage.remove_and_shiftfix(1));
+/// drop(storage.remove_and_shiftfix(6));
+/// assert_eq!(Rc::strong_count(&counter), 7);
+/// storage.defragment();
+/// storage.truncate(4);
+/// assert_eq!(Rc::strong_count(&counter), 5);
+/// storage.remove_and_shiftfix(0);
+/// drop(storage);
+/// assert_eq!(Rc::strong_count(&counter), 1);
+/// ```
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [hole reuse strategy]: enum.HoleReuse.html " "
+/// [`ListStorage::contains_index`]: trait.ListStorage.html#method.contains_index " "
+/// [`SparseStorage::is_hole`]: struct.SparseStorage.html#method.is_hole " "
+/// [`SparseStorage::try_get`]: struct.SparseStorage.html#method.try_get " "
+/// [`SparseStorage::try_get_mut`]: struct.SparseStorage.html#method.try_get_mut " "
+/// [`SparseStorage::take`]: struct.SparseStorage.html#method.take " "
+/// [`SparseStorage::slot`]: struct.SparseStorage.html#method.slot " "
+/// [`SlotRef`]: enum.SparseStorageSlotRef.html " "
+#[repr(transparent)]
+pub struct Slot<T>(MaybeUninit<T>);
+impl<T> Slot<T> {
+    /// Whether holes are big enough to store their links in the hole list.
+    const HAS_LINKS: bool = size_of::<T>() >= size_of::<HoleLinks>();
+
+    const fn new_element(val: T) -> Self {
+        Self(MaybeUninit::new(val))
+    }
+    const fn uninit() -> Self {
+        Self(MaybeUninit::uninit())
+    }
+    /// # Safety
+    /// The slot must contain an element.
+    unsafe fn into_element(self) -> T {
+        self.0.assume_init()
+    }
+    /// # Safety
+    /// The slot must contain an element.
+    unsafe fn element(&self) -> &T {
+        &*self.0.as_ptr()
+    }
+    /// # Safety
+    /// The slot must contain an element.
+    unsafe fn element_mut(&mut self) -> &mut T {
+        &mut *self.0.as_mut_ptr()
+    }
+    /// Returns the links stored in the hole, or unlinked ones if holes are too small to store them, in which case the storage keeps them in its link table.
+    ///
+    /// # Safety
+    /// The slot must be a hole which had its links set.
+    unsafe fn hole_links(&self) -> HoleLinks {
+        if Self::HAS_LINKS {
+            // The element might be less aligned than the links
+            ptr::read_unaligned(self.0.as_ptr().cast::<HoleLinks>())
+        } else {
+            HoleLinks::UNLINKED
+        }
+    }
+    /// Stores the links in the hole, or does nothing if holes are too small to store them.
+    ///
+    /// # Safety
+    /// The slot must be a hole.
+    unsafe fn set_hole_links(&mut self, links: HoleLinks) {
+        if Self::HAS_LINKS {
+            ptr::write_unaligned(self.0.as_mut_ptr().cast::<HoleLinks>(), links);
+        }
+    }
+}
+/// Creates an empty slot, which is required by some storages like `tinyvec::ArrayVec`. Empty slots are never read unless the storage writes an element into them first.
+impl<T> Default for Slot<T> {
+    fn default() -> Self {
+        Self::uninit()
+    }
+}
+impl<T> Debug for Slot<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("Slot { .. }")
+    }
+}
+
+/// A view of a slot inside a sparse storage, returned by [`SparseStorage::slot`].
+///
+/// [`SparseStorage::slot`]: struct.SparseStorage.html#method.slot " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SlotRef<'a, T> {
+    /// The slot contains an element.
+    Element(&'a T),
+    /// The slot is a hole. Both links are `None` if the storage doesn't keep the hole list, which is the case for elements smaller than two pointers until the `Fifo` or `Lifo` hole reuse strategy is selected.
+    Hole {
+        /// The index of the previous hole in the hole list, or `None` if this is the first one.
+        prev: Option<usize>,
+        /// The index of the next hole in the hole list, or `None` if this is the last one.
+        next: Option<usize>,
+    },
+}
+
+/// The links of a hole to its neighbours in the doubly linked hole list.
+///
+/// `usize::MAX` is used instead of `None` to keep the structure two pointers wide. No storage can ever have a slot at that index, since its length would have to exceed `usize::MAX`.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct HoleLinks {
+    prev: usize,
+    next: usize,
+}
+impl HoleLinks {
+    const NONE: usize = usize::MAX;
+    const UNLINKED: Self = Self {
+        prev: Self::NONE,
+        next: Self::NONE,
+    };
+
+    const fn decode(raw: usize) -> Option<usize> {
+        if raw == Self::NONE {
+            None
+        } else {
+            Some(raw)
+        }
+    }
+    const fn prev(self) -> Option<usize> {
+        Self::decode(self.prev)
+    }
+    const fn next(self) -> Option<usize> {
+        Self::decode(self.next)
+    }
+    fn set_prev(&mut self, val: Option<usize>) {
+        self.prev = val.unwrap_or(Self::NONE);
+    }
+    fn set_next(&mut self, val: Option<usize>) {
+        self.next = val.unwrap_or(Self::NONE);
+    }
+}
+impl Debug for HoleLinks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HoleLinks")
+            .field("prev", &self.prev())
+            .field("next", &self.next())
+            .finish()
+    }
+}
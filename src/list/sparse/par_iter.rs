@@ -0,0 +1,142 @@
+use core::fmt::{self, Debug, Formatter};
+use rayon::iter::{plumbing::UnindexedConsumer, IndexedParallelIterator, ParallelIterator};
+use super::Slot;
+use crate::{list::bitmap::Bitmap, ListStorage};
+
+/// A parallel iterator over references to the elements of a [`SparseStorage`], skipping holes.
+///
+/// Created by the `par_elements` method from [`IntoParRefIterator`].
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`IntoParRefIterator`]: trait.IntoParRefIterator.html " "
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+pub struct ParIter<'a, E, I, B>
+where
+    E: Sync + 'a,
+    I: IndexedParallelIterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize> + Sync,
+{
+    inner: I,
+    occupancy: &'a Bitmap<B>,
+}
+impl<'a, E, I, B> ParIter<'a, E, I, B>
+where
+    E: Sync + 'a,
+    I: IndexedParallelIterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize> + Sync,
+{
+    pub(super) fn new(inner: I, occupancy: &'a Bitmap<B>) -> Self {
+        Self { inner, occupancy }
+    }
+}
+impl<'a, E, I, B> ParallelIterator for ParIter<'a, E, I, B>
+where
+    E: Sync + 'a,
+    I: IndexedParallelIterator<Item = &'a Slot<E>>,
+    B: ListStorage<Element = usize> + Sync,
+{
+    type Item = &'a E;
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let occupancy = self.occupancy;
+        self.inner
+            .enumerate()
+            .filter_map(move |(index, slot)| {
+                if occupancy.get(index) {
+                    Some(unsafe {
+                        // SAFETY: the occupancy bit is set
+                        slot.element()
+                    })
+                } else {
+                    None
+                }
+            })
+            .drive_unindexed(consumer)
+    }
+}
+impl<'a, E, I, B> Clone for ParIter<'a, E, I, B>
+where
+    E: Sync + 'a,
+    I: IndexedParallelIterator<Item = &'a Slot<E>> + Clone,
+    B: ListStorage<Element = usize> + Sync,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone(), self.occupancy)
+    }
+}
+impl<'a, E, I, B> Debug for ParIter<'a, E, I, B>
+where
+    E: Sync + 'a,
+    I: IndexedParallelIterator<Item = &'a Slot<E>> + Debug,
+    B: ListStorage<Element = usize> + Sync,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("sparse::ParIter").field(&self.inner).finish()
+    }
+}
+
+/// A parallel iterator over mutable references to the elements of a [`SparseStorage`], skipping holes.
+///
+/// Created by the `par_elements_mut` method from [`IntoParMutIterator`].
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`IntoParMutIterator`]: trait.IntoParMutIterator.html " "
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+pub struct ParIterMut<'a, E, I, B>
+where
+    E: Send + 'a,
+    I: IndexedParallelIterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize> + Sync,
+{
+    inner: I,
+    occupancy: &'a Bitmap<B>,
+}
+impl<'a, E, I, B> ParIterMut<'a, E, I, B>
+where
+    E: Send + 'a,
+    I: IndexedParallelIterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize> + Sync,
+{
+    pub(super) fn new(inner: I, occupancy: &'a Bitmap<B>) -> Self {
+        Self { inner, occupancy }
+    }
+}
+impl<'a, E, I, B> ParallelIterator for ParIterMut<'a, E, I, B>
+where
+    E: Send + 'a,
+    I: IndexedParallelIterator<Item = &'a mut Slot<E>>,
+    B: ListStorage<Element = usize> + Sync,
+{
+    type Item = &'a mut E;
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let occupancy = self.occupancy;
+        self.inner
+            .enumerate()
+            .filter_map(move |(index, slot)| {
+                if occupancy.get(index) {
+                    Some(unsafe {
+                        // SAFETY: as above
+                        slot.element_mut()
+                    })
+                } else {
+                    None
+                }
+            })
+            .drive_unindexed(consumer)
+    }
+}
+impl<'a, E, I, B> Debug for ParIterMut<'a, E, I, B>
+where
+    E: Send + 'a,
+    I: IndexedParallelIterator<Item = &'a mut Slot<E>> + Debug,
+    B: ListStorage<Element = usize> + Sync,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("sparse::ParIterMut").field(&self.inner).finish()
+    }
+}
@@ -0,0 +1,114 @@
+use core::num::NonZeroUsize;
+
+/// A policy which decides when a [`SparseStorage`] should defragment itself automatically.
+///
+/// The policy is checked every time `remove_and_shiftfix` punches a hole, since that is the only operation which can make the storage more fragmented: `add` always fills holes before growing the storage.
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, SparseVec, DefragPolicy, DummyMoveFix};
+///
+/// let mut storage = SparseVec::<DummyMoveFix<u32>>::new();
+/// storage.set_defrag_policy(DefragPolicy::hole_ratio(1, 2));
+/// for value in 0..4 {
+///     storage.add(value.into());
+/// }
+/// storage.remove_and_shiftfix(0);
+/// storage.remove_and_shiftfix(1);
+/// // Exactly half of the storage is holes, which doesn't exceed the ratio yet
+/// assert_eq!(storage.num_holes(), 2);
+/// storage.remove_and_shiftfix(2);
+/// // Now it does, so the storage is defragmented
+/// assert!(storage.is_dense());
+/// assert_eq!(storage.len(), 1);
+/// ```
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DefragPolicy {
+    /// Never defragment automatically. This is the default.
+    Never,
+    /// Defragment the entire storage, fixing elements' indices, as soon as the ratio of the number of holes to the length exceeds `numerator / denominator`.
+    HoleRatio {
+        /// The numerator of the maximum allowed ratio.
+        numerator: usize,
+        /// The denominator of the maximum allowed ratio.
+        denominator: NonZeroUsize,
+    },
+    /// Pop off the holes at the end of the storage as soon as there are more than the specified amount of them. This never moves elements around.
+    TrailingHoles(usize),
+}
+impl DefragPolicy {
+    /// Creates a `HoleRatio` policy with the specified ratio.
+    ///
+    /// # Panics
+    /// Panics if `denominator` is zero.
+    #[track_caller]
+    pub fn hole_ratio(numerator: usize, denominator: usize) -> Self {
+        Self::HoleRatio {
+            numerator,
+            denominator: NonZeroUsize::new(denominator).expect(ZERO_DENOMINATOR_PANIC_MSG),
+        }
+    }
+    /// Returns `true` if the hole ratio policy says that a storage with the specified amount of holes and length should be defragmented, `false` otherwise or if the policy is not `HoleRatio`.
+    pub(super) const fn exceeds_hole_ratio(self, num_holes: usize, len: usize) -> bool {
+        if let Self::HoleRatio {
+            numerator,
+            denominator,
+        } = self
+        {
+            // Widening to u128 avoids overflow, since both sides are products of two usizes
+            num_holes as u128 * denominator.get() as u128 > len as u128 * numerator as u128
+        } else {
+            false
+        }
+    }
+}
+impl Default for DefragPolicy {
+    fn default() -> Self {
+        Self::Never
+    }
+}
+static ZERO_DENOMINATOR_PANIC_MSG: &str = "the denominator of the hole ratio cannot be zero";
+
+/// A strategy which decides which hole a [`SparseStorage`] fills when an element is added.
+///
+/// The `Fifo` and `Lifo` strategies rely on the hole list, which is stored inside of the holes themselves if the elements are at least two pointers big, or in a separate table of two words per slot otherwise. Storages of such small elements start off with `LowestIndex`, which needs nothing but the occupancy bitmap, and only start keeping the table once switched to another strategy. From then on, the list is kept up to date with every strategy, so the order in which the holes were punched is never lost when switching between them.
+///
+/// # Example
+/// ```rust
+/// use granite::{ListStorage, SparseVec, HoleReuse, DummyMoveFix};
+///
+/// let mut storage = SparseVec::<DummyMoveFix<String>>::new();
+/// for value in 0..4 {
+///     storage.add(value.to_string().into());
+/// }
+/// storage.remove_and_shiftfix(2);
+/// storage.remove_and_shiftfix(0);
+/// storage.remove_and_shiftfix(3);
+///
+/// // The default is to fill the hole which was created first:
+/// assert_eq!(storage.add("4".to_string().into()), 2);
+/// // Other strategies can be switched to at any time:
+/// storage.set_hole_reuse(HoleReuse::LowestIndex);
+/// assert_eq!(storage.add("5".to_string().into()), 0);
+/// assert_eq!(storage.add("6".to_string().into()), 3);
+/// ```
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HoleReuse {
+    /// Fill the hole which was created the earliest. This is the default for elements which are at least two pointers big, and the one returned by `HoleReuse::default()`.
+    Fifo,
+    /// Fill the hole which was created the latest, which is likely to still be in cache.
+    Lifo,
+    /// Fill the hole with the lowest index, keeping the elements packed towards the start of the storage so that the holes at the end can be popped off.
+    ///
+    /// The lowest hole is found through the occupancy bitmap of the storage, which skips a whole word of occupied slots at a time, so removing an element stays *O(1)* and switching to or from this strategy costs nothing. This is the default for elements which are smaller than two pointers, since it doesn't need the link table.
+    LowestIndex,
+}
+impl Default for HoleReuse {
+    fn default() -> Self {
+        Self::Fifo
+    }
+}
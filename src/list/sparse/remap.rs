@@ -0,0 +1,66 @@
+use core::{iter::Copied, slice};
+use alloc::vec::Vec;
+
+/// A table which maps the indices of elements of a [`SparseStorage`] before defragmentation to their indices after it.
+///
+/// Returned by [`defragment_with_remap`]. Only the elements which were actually relocated are stored, so the table takes up memory proportional to the number of holes and not to the size of the storage.
+///
+/// [`SparseStorage`]: struct.SparseStorage.html " "
+/// [`defragment_with_remap`]: struct.SparseStorage.html#method.defragment_with_remap " "
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyRemap {
+    /// Pairs of old and new indices, with the old indices in descending order and the new ones in ascending order.
+    moves: Vec<(usize, usize)>,
+    old_len: usize,
+    new_len: usize,
+}
+impl KeyRemap {
+    pub(super) const fn new(moves: Vec<(usize, usize)>, old_len: usize, new_len: usize) -> Self {
+        Self {
+            moves,
+            old_len,
+            new_len,
+        }
+    }
+
+    /// Returns the new index of the element which was at index `old` before defragmentation, or `None` if there was a hole or the index was out of bounds.
+    pub fn get(&self, old: usize) -> Option<usize> {
+        if old < self.new_len {
+            // Holes in this region got filled by moved elements, everything else stayed in place
+            match self.moves.binary_search_by(|&(_, new)| new.cmp(&old)) {
+                Ok(..) => None,
+                Err(..) => Some(old),
+            }
+        } else if old < self.old_len {
+            // Elements in this region have all been moved, everything else was a hole
+            self.moves
+                .binary_search_by(|&(moved, _)| old.cmp(&moved))
+                .ok()
+                .map(|i| self.moves[i].1)
+        } else {
+            None
+        }
+    }
+    /// Returns an iterator over the pairs of old and new indices of the elements which were relocated, in the order in which they were moved.
+    ///
+    /// Elements which are not in this list have kept their index.
+    pub fn moves(&self) -> Copied<slice::Iter<'_, (usize, usize)>> {
+        self.moves.iter().copied()
+    }
+    /// Returns the number of elements which were relocated.
+    pub fn num_moves(&self) -> usize {
+        self.moves.len()
+    }
+    /// Returns `true` if no elements were relocated, `false` otherwise.
+    pub fn is_identity(&self) -> bool {
+        self.moves.is_empty()
+    }
+    /// Returns the length of the storage before defragmentation.
+    pub const fn old_len(&self) -> usize {
+        self.old_len
+    }
+    /// Returns the length of the storage after defragmentation.
+    pub const fn new_len(&self) -> usize {
+        self.new_len
+    }
+}
@@ -1,7 +1,7 @@
 use core::slice;
 
 use tinyvec::{Array, ArrayVec, SliceVec, TinyVec};
-use crate::{ListStorage, IntoRefIterator, IntoMutIterator};
+use crate::{ListStorage, IntoRefIterator, IntoMutIterator, DenseList, CapacityError};
 
 unsafe impl<A: Array> ListStorage for TinyVec<A> {
     type Element = A::Item;
@@ -11,7 +11,7 @@ unsafe impl<A: Array> ListStorage for TinyVec<A> {
         Self::with_capacity(capacity)
     }
     fn insert(&mut self, index: usize, element: Self::Element) {
-        self.insert(index, element)
+        self.insert(index, element);
     }
     fn remove(&mut self, index: usize) -> Self::Element {
         self.remove(index)
@@ -36,7 +36,7 @@ unsafe impl<A: Array> ListStorage for TinyVec<A> {
         Self::new()
     }
     fn push(&mut self, element: Self::Element) {
-        self.push(element)
+        self.push(element);
     }
     fn pop(&mut self) -> Option<Self::Element> {
         self.pop()
@@ -45,13 +45,19 @@ unsafe impl<A: Array> ListStorage for TinyVec<A> {
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
-        self.shrink_to_fit()
+        self.shrink_to_fit();
     }
     fn truncate(&mut self, len: usize) {
-        self.truncate(len)
+        self.truncate(len);
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        self.split_off(at)
+    }
+    fn append(&mut self, other: &mut Self) {
+        self.append(other);
     }
 }
 impl<'a, A: Array> IntoRefIterator<'a> for TinyVec<A>
@@ -74,6 +80,7 @@ where
         self.as_mut_slice().iter_mut()
     }
 }
+unsafe impl<A: Array> DenseList for TinyVec<A> {}
 
 unsafe impl<A: Array> ListStorage for ArrayVec<A> {
     type Element = A::Item;
@@ -87,7 +94,7 @@ unsafe impl<A: Array> ListStorage for ArrayVec<A> {
         Self::new()
     }
     fn insert(&mut self, index: usize, element: Self::Element) {
-        self.insert(index, element)
+        self.insert(index, element);
     }
     fn remove(&mut self, index: usize) -> Self::Element {
         self.remove(index)
@@ -111,7 +118,7 @@ unsafe impl<A: Array> ListStorage for ArrayVec<A> {
         Self::new()
     }
     fn push(&mut self, element: Self::Element) {
-        self.push(element)
+        self.push(element);
     }
     fn pop(&mut self) -> Option<Self::Element> {
         self.pop()
@@ -120,7 +127,13 @@ unsafe impl<A: Array> ListStorage for ArrayVec<A> {
         A::CAPACITY
     }
     fn truncate(&mut self, len: usize) {
-        self.truncate(len)
+        self.truncate(len);
+    }
+    fn split_off(&mut self, at: usize) -> Self {
+        self.split_off(at)
+    }
+    fn append(&mut self, other: &mut Self) {
+        self.append(other);
     }
 }
 impl<'a, A: Array> IntoRefIterator<'a> for ArrayVec<A>
@@ -143,8 +156,9 @@ where
         self.as_mut_slice().iter_mut()
     }
 }
+unsafe impl<A: Array> DenseList for ArrayVec<A> {}
 
-unsafe impl<'s, T: Default> ListStorage for SliceVec<'s, T> {
+unsafe impl<T: Default> ListStorage for SliceVec<'_, T> {
     type Element = T;
     const CAPACITY: Option<usize> = None;
 
@@ -153,7 +167,7 @@ unsafe impl<'s, T: Default> ListStorage for SliceVec<'s, T> {
         Self::new()
     }
     fn insert(&mut self, index: usize, element: Self::Element) {
-        self.insert(index, element)
+        self.insert(index, element);
     }
     fn remove(&mut self, index: usize) -> Self::Element {
         self.remove(index)
@@ -178,7 +192,7 @@ unsafe impl<'s, T: Default> ListStorage for SliceVec<'s, T> {
         unimplemented!("cannot create a SliceVec out of thin air")
     }
     fn push(&mut self, element: Self::Element) {
-        self.push(element)
+        self.push(element);
     }
     fn pop(&mut self) -> Option<Self::Element> {
         self.pop()
@@ -187,7 +201,34 @@ unsafe impl<'s, T: Default> ListStorage for SliceVec<'s, T> {
         self.capacity()
     }
     fn truncate(&mut self, len: usize) {
-        self.truncate(len)
+        self.truncate(len);
+    }
+    // SliceVec can't reallocate but doesn't have a fixed CAPACITY either,
+    // so the default implementations wouldn't catch it being full.
+    #[track_caller]
+    fn try_insert(
+        &mut self,
+        index: usize,
+        element: Self::Element,
+    ) -> Result<(), CapacityError<Self::Element>> {
+        assert!(index <= self.len(), "index out of bounds");
+        if self.len() < self.capacity() {
+            self.insert(index, element);
+            Ok(())
+        } else {
+            Err(CapacityError::new(element))
+        }
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        let fits = self
+            .len()
+            .checked_add(additional)
+            .map_or(false, |required| required <= self.capacity());
+        if fits {
+            Ok(())
+        } else {
+            Err(CapacityError::new(()))
+        }
     }
 }
 impl<'s: 'a, 'a, T> IntoRefIterator<'a> for SliceVec<'s, T> {
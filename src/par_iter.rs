@@ -0,0 +1,30 @@
+use rayon::iter::ParallelIterator;
+
+/// Types which have corresponding immutably borrowing parallel iterators.
+///
+/// This is the parallel counterpart of [`IntoRefIterator`], analogous to the `par_iter` method which `rayon` provides for `Vec`. The method is named differently to avoid ambiguity with that one when both traits are in scope.
+///
+/// [`IntoRefIterator`]: trait.IntoRefIterator.html " "
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+pub trait IntoParRefIterator<'a> {
+    /// The item type over references to which iteration will be performed.
+    type Item: Sync + 'a;
+    /// The resulting parallel iterator type.
+    type ParIter: ParallelIterator<Item = &'a Self::Item>;
+    /// Borrows the value into a by-reference parallel iterator with immutable access.
+    fn par_elements(&'a self) -> Self::ParIter;
+}
+/// Types which have corresponding *mutably* borrowing parallel iterators.
+///
+/// This is the parallel counterpart of [`IntoMutIterator`], analogous to the `par_iter_mut` method which `rayon` provides for `Vec`.
+///
+/// [`IntoMutIterator`]: trait.IntoMutIterator.html " "
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "rayon")))]
+pub trait IntoParMutIterator<'a> {
+    /// The item type over mutable references to which iteration will be performed.
+    type Item: Send + 'a;
+    /// The resulting parallel iterator type.
+    type ParIterMut: ParallelIterator<Item = &'a mut Self::Item>;
+    /// Borrows the value into a by-reference parallel iterator with mutable access.
+    fn par_elements_mut(&'a mut self) -> Self::ParIterMut;
+}
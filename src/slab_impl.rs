@@ -1,5 +1,6 @@
-use slab::Slab;
-use super::Storage;
+use core::iter::Map;
+use slab::{self, Slab};
+use super::{Storage, StorageIter};
 
 unsafe impl<T> Storage for Slab<T> {
     type Key = usize;
@@ -39,9 +40,32 @@ unsafe impl<T> Storage for Slab<T> {
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
-        self.shrink_to_fit()
+        self.shrink_to_fit();
+    }
+}
+impl<'a, T: 'a> StorageIter<'a> for Slab<T> {
+    type Keys = Map<slab::Iter<'a, T>, fn((usize, &'a T)) -> usize>;
+    type Values = Map<slab::Iter<'a, T>, fn((usize, &'a T)) -> &'a T>;
+    type ValuesMut = Map<slab::IterMut<'a, T>, fn((usize, &'a mut T)) -> &'a mut T>;
+    type Iter = slab::Iter<'a, T>;
+    type IterMut = slab::IterMut<'a, T>;
+
+    fn keys(&'a self) -> Self::Keys {
+        self.iter().map(|(key, _)| key)
+    }
+    fn values(&'a self) -> Self::Values {
+        self.iter().map(|(_, element)| element)
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        self.iter_mut().map(|(_, element)| element)
+    }
+    fn iter(&'a self) -> Self::Iter {
+        self.iter()
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        self.iter_mut()
     }
 }
@@ -1,6 +1,13 @@
 use core::fmt::Debug;
-use slotmap::{SlotMap, HopSlotMap, DenseSlotMap, Key, Slottable};
-use super::Storage;
+use slotmap::{self, hop, dense, SlotMap, HopSlotMap, DenseSlotMap, Key, Slottable};
+use super::{Storage, StorageIter, CapacityError};
+
+// Slot maps panic upon insertion if the number of elements would reach u32::MAX.
+const MAX_ELEMENTS: usize = (u32::MAX - 1) as usize;
+fn fits_max_elements(len: usize, additional: usize) -> bool {
+    len.checked_add(additional)
+        .map_or(false, |required| required <= MAX_ELEMENTS)
+}
 
 unsafe impl<K, V> Storage for SlotMap<K, V>
 where
@@ -43,11 +50,53 @@ where
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
         // FIXME slotmaps don't have a shrink_to_fir method
     }
+    fn try_add(&mut self, element: Self::Element) -> Result<Self::Key, CapacityError<Self::Element>> {
+        if fits_max_elements(self.len(), 1) {
+            Ok(self.insert(element))
+        } else {
+            Err(CapacityError::new(element))
+        }
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        if fits_max_elements(self.len(), additional) {
+            self.reserve(additional);
+            Ok(())
+        } else {
+            Err(CapacityError::new(()))
+        }
+    }
+}
+impl<'a, K, V> StorageIter<'a> for SlotMap<K, V>
+where
+    K: Key + Debug + Eq + 'a,
+    V: Slottable + 'a,
+{
+    type Keys = slotmap::Keys<'a, K, V>;
+    type Values = slotmap::Values<'a, K, V>;
+    type ValuesMut = slotmap::ValuesMut<'a, K, V>;
+    type Iter = slotmap::Iter<'a, K, V>;
+    type IterMut = slotmap::IterMut<'a, K, V>;
+
+    fn keys(&'a self) -> Self::Keys {
+        self.keys()
+    }
+    fn values(&'a self) -> Self::Values {
+        self.values()
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        self.values_mut()
+    }
+    fn iter(&'a self) -> Self::Iter {
+        self.iter()
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        self.iter_mut()
+    }
 }
 
 unsafe impl<K, V> Storage for HopSlotMap<K, V>
@@ -91,11 +140,53 @@ where
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
         // FIXME slotmaps don't have a shrink_to_fir method
     }
+    fn try_add(&mut self, element: Self::Element) -> Result<Self::Key, CapacityError<Self::Element>> {
+        if fits_max_elements(self.len(), 1) {
+            Ok(self.insert(element))
+        } else {
+            Err(CapacityError::new(element))
+        }
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        if fits_max_elements(self.len(), additional) {
+            self.reserve(additional);
+            Ok(())
+        } else {
+            Err(CapacityError::new(()))
+        }
+    }
+}
+impl<'a, K, V> StorageIter<'a> for HopSlotMap<K, V>
+where
+    K: Key + Debug + Eq + 'a,
+    V: Slottable + 'a,
+{
+    type Keys = hop::Keys<'a, K, V>;
+    type Values = hop::Values<'a, K, V>;
+    type ValuesMut = hop::ValuesMut<'a, K, V>;
+    type Iter = hop::Iter<'a, K, V>;
+    type IterMut = hop::IterMut<'a, K, V>;
+
+    fn keys(&'a self) -> Self::Keys {
+        self.keys()
+    }
+    fn values(&'a self) -> Self::Values {
+        self.values()
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        self.values_mut()
+    }
+    fn iter(&'a self) -> Self::Iter {
+        self.iter()
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        self.iter_mut()
+    }
 }
 
 unsafe impl<K, V> Storage for DenseSlotMap<K, V>
@@ -139,9 +230,51 @@ where
         self.capacity()
     }
     fn reserve(&mut self, additional: usize) {
-        self.reserve(additional)
+        self.reserve(additional);
     }
     fn shrink_to_fit(&mut self) {
         // FIXME slotmaps don't have a shrink_to_fir method
     }
+    fn try_add(&mut self, element: Self::Element) -> Result<Self::Key, CapacityError<Self::Element>> {
+        if fits_max_elements(self.len(), 1) {
+            Ok(self.insert(element))
+        } else {
+            Err(CapacityError::new(element))
+        }
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        if fits_max_elements(self.len(), additional) {
+            self.reserve(additional);
+            Ok(())
+        } else {
+            Err(CapacityError::new(()))
+        }
+    }
+}
+impl<'a, K, V> StorageIter<'a> for DenseSlotMap<K, V>
+where
+    K: Key + Debug + Eq + 'a,
+    V: Slottable + 'a,
+{
+    type Keys = dense::Keys<'a, K, V>;
+    type Values = dense::Values<'a, K, V>;
+    type ValuesMut = dense::ValuesMut<'a, K, V>;
+    type Iter = dense::Iter<'a, K, V>;
+    type IterMut = dense::IterMut<'a, K, V>;
+
+    fn keys(&'a self) -> Self::Keys {
+        self.keys()
+    }
+    fn values(&'a self) -> Self::Values {
+        self.values()
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        self.values_mut()
+    }
+    fn iter(&'a self) -> Self::Iter {
+        self.iter()
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        self.iter_mut()
+    }
 }
@@ -0,0 +1,218 @@
+use core::{num::NonZeroIsize, iter::Map};
+use crate::{ListStorage, MoveFix, Storage, StorageIter, CapacityError};
+
+/// A wrapper around a storage type which pairs every key with a generation counter to detect stale keys.
+///
+/// Most storages reuse the keys of removed elements for newly added ones: `Slab` and `SparseStorage` fill in vacant slots before growing. If a data structure keeps a key after the element it points to was removed, that key will silently point to whatever element took its place. `Versioned` solves this by storing the generation of each element alongside it and handing out [`VersionedKey`]s, which remember the generation of the element they were created for. Checked methods return `None` for stale keys, while `remove` panics on them.
+///
+/// The generation counter is shared by the entire storage and is incremented on every addition, which means that a key can only be mistaken for a fresh one after 2<sup>64</sup> additions. This works with *any* storage type, including `SparseStorage`, `Slab` and custom ones, without the need for a separate generation table.
+///
+/// # Example
+/// ```rust
+/// use granite::{
+///     Storage,
+///     SparseVec,
+///     Versioned,
+///     VersionedElement,
+/// };
+///
+/// let mut storage = Versioned::<u32, SparseVec<VersionedElement<u32>>>::new();
+/// let old_key = storage.add(0);
+/// assert_eq!(storage.remove(&old_key), 0);
+///
+/// // The hole left by the removed element is reused...
+/// let new_key = storage.add(1);
+/// assert_eq!(old_key.key, new_key.key);
+/// // ...but the old key doesn't alias the new element:
+/// assert_eq!(storage.get(&old_key), None);
+/// assert!(!storage.contains_key(&old_key));
+/// assert_eq!(storage.get(&new_key), Some(&1));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Versioned<E, S>
+where
+    S: Storage<Element = VersionedElement<E>>,
+{
+    storage: S,
+    next_generation: u64,
+}
+impl<E, S> Versioned<E, S>
+where
+    S: Storage<Element = VersionedElement<E>>,
+{
+    /// Consumes the versioned storage and returns its inner storage.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+    /// Returns the generation which will be assigned to the next added element.
+    pub const fn next_generation(&self) -> u64 {
+        self.next_generation
+    }
+    fn check_generation(&self, key: &VersionedKey<S::Key>) -> Option<&VersionedElement<E>> {
+        self.storage
+            .get(&key.key)
+            .filter(|x| x.generation == key.generation)
+    }
+}
+static STALE_KEY_PANIC_MSG: &str = "\
+the specified key is stale: the element it pointed to has been removed";
+unsafe impl<E, S> Storage for Versioned<E, S>
+where
+    S: Storage<Element = VersionedElement<E>>,
+{
+    type Key = VersionedKey<S::Key>;
+    type Element = E;
+    const CAPACITY: Option<usize> = S::CAPACITY;
+
+    fn add(&mut self, element: Self::Element) -> Self::Key {
+        let generation = self.next_generation;
+        self.next_generation = generation.wrapping_add(1);
+        let key = self.storage.add(VersionedElement {
+            generation,
+            element,
+        });
+        VersionedKey { key, generation }
+    }
+    #[track_caller]
+    fn remove(&mut self, key: &Self::Key) -> Self::Element {
+        assert!(self.contains_key(key), "{}", STALE_KEY_PANIC_MSG);
+        self.storage.remove(&key.key).element
+    }
+    fn len(&self) -> usize {
+        self.storage.len()
+    }
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            storage: S::with_capacity(capacity),
+            next_generation: 0,
+        }
+    }
+    unsafe fn get_unchecked(&self, key: &Self::Key) -> &Self::Element {
+        &self.storage.get_unchecked(&key.key).element
+    }
+    unsafe fn get_unchecked_mut(&mut self, key: &Self::Key) -> &mut Self::Element {
+        &mut self.storage.get_unchecked_mut(&key.key).element
+    }
+    fn contains_key(&self, key: &Self::Key) -> bool {
+        self.check_generation(key).is_some()
+    }
+    fn get(&self, key: &Self::Key) -> Option<&Self::Element> {
+        self.check_generation(key).map(|x| &x.element)
+    }
+    fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Element> {
+        self.storage
+            .get_mut(&key.key)
+            .filter(|x| x.generation == key.generation)
+            .map(|x| &mut x.element)
+    }
+    fn new() -> Self {
+        Self {
+            storage: S::new(),
+            next_generation: 0,
+        }
+    }
+    fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+    fn reserve(&mut self, additional: usize) {
+        self.storage.reserve(additional);
+    }
+    fn shrink_to_fit(&mut self) {
+        self.storage.shrink_to_fit();
+    }
+    fn try_add(&mut self, element: Self::Element) -> Result<Self::Key, CapacityError<Self::Element>> {
+        let generation = self.next_generation;
+        let key = self
+            .storage
+            .try_add(VersionedElement {
+                generation,
+                element,
+            })
+            .map_err(|e| e.map(|x| x.element))?;
+        self.next_generation = generation.wrapping_add(1);
+        Ok(VersionedKey { key, generation })
+    }
+    fn try_reserve(&mut self, additional: usize) -> Result<(), CapacityError> {
+        self.storage.try_reserve(additional)
+    }
+}
+impl<'a, E, S> StorageIter<'a> for Versioned<E, S>
+where
+    S: StorageIter<'a, Element = VersionedElement<E>>,
+    E: 'a,
+{
+    type Keys = Map<S::Iter, fn((S::Key, &'a VersionedElement<E>)) -> VersionedKey<S::Key>>;
+    type Values = Map<S::Values, fn(&'a VersionedElement<E>) -> &'a E>;
+    type ValuesMut = Map<S::ValuesMut, fn(&'a mut VersionedElement<E>) -> &'a mut E>;
+    type Iter = Map<
+        S::Iter,
+        fn((S::Key, &'a VersionedElement<E>)) -> (VersionedKey<S::Key>, &'a E),
+    >;
+    type IterMut = Map<
+        S::IterMut,
+        fn((S::Key, &'a mut VersionedElement<E>)) -> (VersionedKey<S::Key>, &'a mut E),
+    >;
+
+    fn keys(&'a self) -> Self::Keys {
+        self.storage.iter().map(|(key, x)| VersionedKey {
+            key,
+            generation: x.generation,
+        })
+    }
+    fn values(&'a self) -> Self::Values {
+        self.storage.values().map(|x| &x.element)
+    }
+    fn values_mut(&'a mut self) -> Self::ValuesMut {
+        self.storage.values_mut().map(|x| &mut x.element)
+    }
+    fn iter(&'a self) -> Self::Iter {
+        self.storage.iter().map(|(key, x)| {
+            let key = VersionedKey {
+                key,
+                generation: x.generation,
+            };
+            (key, &x.element)
+        })
+    }
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        self.storage.iter_mut().map(|(key, x)| {
+            let key = VersionedKey {
+                key,
+                generation: x.generation,
+            };
+            (key, &mut x.element)
+        })
+    }
+}
+
+/// A key into a [`Versioned`] storage, consisting of a key into the inner storage and the generation of the element it was created for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VersionedKey<K> {
+    /// The key of the element in the inner storage.
+    pub key: K,
+    /// The generation of the element at the time when it was added.
+    pub generation: u64,
+}
+
+/// An element inside a versioned storage.
+///
+/// This is an opaque structure, only used for the purpose of a `Versioned` storage being validly declarable, since the inner storage has to store the generation of each element alongside it.
+///
+/// The `MoveFix` implementation does nothing when notified: keys handed out by [`Versioned`] become stale instead of being fixed if the inner storage shifts elements around.
+#[derive(Copy, Clone, Debug)]
+pub struct VersionedElement<T> {
+    generation: u64,
+    element: T,
+}
+impl<T> MoveFix for VersionedElement<T> {
+    unsafe fn fix_shift<S>(_: &mut S, _: usize, _: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+    }
+    unsafe fn fix_move<S>(_: &mut S, _: usize, _: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+    }
+}
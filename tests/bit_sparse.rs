@@ -0,0 +1,277 @@
+#![cfg(feature = "alloc")]
+
+use core::{cell::Cell, num::NonZeroIsize};
+use granite::{BitSparseVec, DummyMoveFix, IntoMutIterator, IntoRefIterator, ListStorage, MoveFix};
+
+type Element = DummyMoveFix<u32>;
+
+/// Creates a storage with the specified amount of elements, each equal to its index, and punches holes at the specified indices.
+fn with_holes(len: u32, holes: &[usize]) -> BitSparseVec<Element> {
+    let mut storage: BitSparseVec<Element> = ListStorage::new();
+    for value in 0..len {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+fn values(storage: &BitSparseVec<Element>) -> Vec<u32> {
+    IntoRefIterator::iter(storage).map(|x| x.0).collect()
+}
+
+#[test]
+fn iteration_skips_holes_across_words() {
+    let holes = (0..300).filter(|i| i % 100 != 7).collect::<Vec<_>>();
+    let storage = with_holes(300, &holes);
+    assert_eq!(storage.num_holes(), 297);
+    assert_eq!(values(&storage), [7, 107, 207]);
+    let indexed = IntoRefIterator::iter(&storage)
+        .indexed()
+        .rev()
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert_eq!(indexed, [207, 107, 7]);
+    assert_eq!(IntoRefIterator::iter(&storage).len(), 3);
+
+    // Meeting in the middle never yields an element twice
+    let mut iter = IntoRefIterator::iter(&storage);
+    assert_eq!(iter.next().map(|x| x.0), Some(7));
+    assert_eq!(iter.next_back().map(|x| x.0), Some(207));
+    assert_eq!(iter.next_back().map(|x| x.0), Some(107));
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+}
+
+#[test]
+fn mutable_iteration_skips_holes() {
+    let mut storage = with_holes(6, &[1, 4]);
+    for element in IntoMutIterator::iter_mut(&mut storage) {
+        element.0 *= 10;
+    }
+    assert_eq!(values(&storage), [0, 20, 30, 50]);
+    assert_eq!(storage.num_holes(), 2);
+}
+
+#[test]
+fn add_fills_the_lowest_hole() {
+    let holes = [190, 3, 150, 64, 63, 129, 0];
+    let mut storage = with_holes(200, &holes);
+    let mut sorted = holes.to_vec();
+    sorted.sort_unstable();
+    for &hole in &sorted {
+        assert_eq!(ListStorage::add(&mut storage, 1000.into()), hole);
+    }
+    assert!(storage.is_dense());
+    assert_eq!(ListStorage::add(&mut storage, 1000.into()), 200);
+}
+
+#[test]
+fn holes_can_be_inspected_without_panicking() {
+    let mut storage = with_holes(5, &[3, 1]);
+    assert!(storage.is_hole(1));
+    assert!(!storage.is_hole(2));
+    assert!(!storage.is_hole(5));
+    assert!(!ListStorage::contains_index(&storage, 3));
+    assert!(ListStorage::contains_index(&storage, 4));
+    assert_eq!(storage.try_get(1), None);
+    assert_eq!(storage.try_get(4), Some(&4.into()));
+    assert_eq!(storage.try_get(5), None);
+    *storage.try_get_mut(2).unwrap() = 20.into();
+    assert!(storage.try_get_mut(3).is_none());
+    assert_eq!(values(&storage), [0, 20, 4]);
+    assert_eq!(ListStorage::get(&storage, 5), None);
+}
+
+#[test]
+#[should_panic(expected = "hole")]
+fn get_of_a_hole_panics() {
+    let storage = with_holes(3, &[1]);
+    ListStorage::get(&storage, 1);
+}
+
+#[test]
+fn insert_and_remove_shift_the_bitmap() {
+    let mut storage = with_holes(70, &[1, 65]);
+    ListStorage::insert(&mut storage, 0, 100.into());
+    assert!(storage.is_hole(2));
+    assert!(storage.is_hole(66));
+    assert!(!storage.is_hole(65));
+    assert_eq!(storage.num_holes(), 2);
+    assert_eq!(ListStorage::remove(&mut storage, 3).0, 2);
+    assert!(storage.is_hole(2));
+    assert!(storage.is_hole(65));
+    assert_eq!(ListStorage::len(&storage), 70);
+    assert_eq!(ListStorage::add(&mut storage, 200.into()), 2);
+    assert_eq!(ListStorage::add(&mut storage, 200.into()), 65);
+    assert!(storage.is_dense());
+}
+
+#[test]
+#[should_panic(expected = "hole")]
+fn raw_remove_of_a_hole_panics() {
+    let mut storage = with_holes(3, &[1]);
+    ListStorage::remove(&mut storage, 1);
+}
+
+#[test]
+fn pop_and_truncate_discard_holes() {
+    let mut storage = with_holes(6, &[5, 4, 1]);
+    assert_eq!(ListStorage::pop(&mut storage), Some(3.into()));
+    assert_eq!(ListStorage::len(&storage), 3);
+    assert_eq!(storage.num_holes(), 1);
+    ListStorage::truncate(&mut storage, 1);
+    assert!(storage.is_dense());
+    assert_eq!(values(&storage), [0]);
+
+    let mut storage = with_holes(2, &[0, 1]);
+    assert_eq!(ListStorage::pop(&mut storage), None);
+    assert!(ListStorage::is_empty(&storage));
+    assert!(storage.is_dense());
+}
+
+/// An element which knows its own index and checks that every move it's notified about starts there.
+#[derive(Debug)]
+struct Tracked {
+    value: u32,
+    index: usize,
+    moves: usize,
+}
+impl MoveFix for Tracked {
+    unsafe fn fix_shift<S>(storage: &mut S, shifted_from: usize, shifted_by: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        for index in shifted_from..storage.len() {
+            if storage.contains_index(index) {
+                let element = storage.get_mut(index).unwrap();
+                element.index = (element.index as isize + shifted_by.get()) as usize;
+            }
+        }
+    }
+    unsafe fn fix_move<S>(storage: &mut S, previous_index: usize, current_index: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        let element = storage.get_mut(current_index).unwrap();
+        assert_eq!(element.index, previous_index);
+        element.index = current_index;
+        element.moves += 1;
+    }
+}
+
+#[test]
+fn defragment_and_fix_notifies_relocated_elements_once() {
+    let mut storage: BitSparseVec<Tracked> = ListStorage::new();
+    for value in 0..10 {
+        let index = ListStorage::add(
+            &mut storage,
+            Tracked {
+                value,
+                index: 0,
+                moves: 0,
+            },
+        );
+        ListStorage::get_mut(&mut storage, index).unwrap().index = index;
+    }
+    for &index in &[1, 2, 5, 9] {
+        storage.remove_and_shiftfix(index);
+    }
+    storage.defragment_and_fix();
+    assert!(storage.is_dense());
+    let state = IntoRefIterator::iter(&storage)
+        .indexed()
+        .map(|(index, element)| {
+            assert_eq!(element.index, index);
+            (element.value, element.moves)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(state, [(0, 0), (8, 1), (7, 1), (3, 0), (4, 0), (6, 1)]);
+
+    // Shifting fixes the elements from the insertion point onwards, which includes the new
+    // element, so it starts out with the index it would've had before the shift
+    ListStorage::insert_and_shiftfix(
+        &mut storage,
+        1,
+        Tracked {
+            value: 100,
+            index: 0,
+            moves: 0,
+        },
+    );
+    for (index, element) in IntoRefIterator::iter(&storage).indexed() {
+        assert_eq!(element.index, index);
+    }
+}
+
+#[test]
+fn clones_compare_equal() {
+    let storage = with_holes(10, &[0, 4, 9]);
+    let cloned = storage.clone();
+    assert_eq!(cloned, storage);
+    assert_eq!(cloned.num_holes(), 3);
+    assert!(cloned.is_hole(4));
+    assert_ne!(cloned, with_holes(10, &[0, 5, 9]));
+    assert_eq!(
+        format!("{:?}", with_holes(3, &[1])),
+        "[Some(DummyMoveFix(0)), None, Some(DummyMoveFix(2))]"
+    );
+}
+
+/// An element which counts how many of its kind are alive through a shared counter.
+struct Counted<'a>(&'a Cell<usize>);
+impl<'a> Counted<'a> {
+    fn new(alive: &'a Cell<usize>) -> Self {
+        alive.set(alive.get() + 1);
+        Self(alive)
+    }
+}
+impl<'a> Clone for Counted<'a> {
+    fn clone(&self) -> Self {
+        Self::new(self.0)
+    }
+}
+impl<'a> Drop for Counted<'a> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+impl<'a> MoveFix for Counted<'a> {
+    unsafe fn fix_shift<S>(_: &mut S, _: usize, _: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+    }
+    unsafe fn fix_move<S>(_: &mut S, _: usize, _: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+    }
+}
+
+#[test]
+fn elements_are_dropped_exactly_once() {
+    let alive = Cell::new(0);
+    {
+        let mut storage: BitSparseVec<Counted<'_>> = ListStorage::new();
+        for _ in 0..10 {
+            ListStorage::add(&mut storage, Counted::new(&alive));
+        }
+        drop(storage.remove_and_shiftfix(2));
+        drop(storage.remove_and_shiftfix(9));
+        assert_eq!(alive.get(), 8);
+        ListStorage::add(&mut storage, Counted::new(&alive));
+        assert_eq!(alive.get(), 9);
+        drop(storage.clone());
+        assert_eq!(alive.get(), 9);
+        storage.remove_and_shiftfix(4);
+        storage.defragment();
+        assert_eq!(alive.get(), 8);
+        ListStorage::truncate(&mut storage, 5);
+        assert_eq!(alive.get(), 5);
+        storage.remove_and_shiftfix(0);
+        assert_eq!(alive.get(), 4);
+    }
+    assert_eq!(alive.get(), 0);
+}
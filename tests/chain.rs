@@ -0,0 +1,655 @@
+#![cfg(feature = "alloc")]
+
+use core::num::NonZeroIsize;
+use granite::{Chain, DummyMoveFix, IntoMutIterator, IntoRefIterator, List, ListStorage, MoveFix};
+
+type VecChain = Chain<u32, Vec<u32>, Vec<Vec<u32>>, Vec<usize>>;
+
+/// A tiny deterministic pseudorandom number generator, so that the tests don't need a dependency.
+struct Lcg(u64);
+impl Lcg {
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        ((self.0 >> 33) as usize) % bound
+    }
+}
+
+/// Returns the lengths of the storages of the chain in order.
+fn storage_lens(chain: &VecChain) -> Vec<usize> {
+    chain
+        .iter()
+        .map(|storage| IntoRefIterator::iter(&storage).count())
+        .collect()
+}
+
+/// Creates a chain with the specified limit and the specified amount of elements, each equal to its index.
+fn chain_of(limit: usize, len: u32) -> VecChain {
+    let mut chain = VecChain::new();
+    chain.set_limit(limit);
+    for value in 0..len {
+        ListStorage::push(&mut chain, value);
+    }
+    chain
+}
+
+/// Checks that every index of the chain leads to the same element as in the model.
+fn assert_matches(chain: &VecChain, model: &[u32]) {
+    assert_eq!(ListStorage::len(chain), model.len());
+    for (index, element) in model.iter().enumerate() {
+        assert_eq!(ListStorage::get(chain, index), Some(element));
+    }
+    assert_eq!(ListStorage::get(chain, model.len()), None);
+    assert_eq!(chain.elements().copied().collect::<Vec<_>>(), model);
+}
+
+#[test]
+fn lookups_match_a_vec_model() {
+    let mut rng = Lcg(1);
+    let mut chain = VecChain::new();
+    chain.set_limit(4);
+    let mut model = Vec::new();
+    for value in 0..2000 {
+        match rng.below(6) {
+            0 | 1 => {
+                ListStorage::push(&mut chain, value);
+                model.push(value);
+            }
+            2 | 3 => {
+                let index = rng.below(model.len() + 1);
+                ListStorage::insert(&mut chain, index, value);
+                model.insert(index, value);
+            }
+            4 if !model.is_empty() => {
+                let index = rng.below(model.len());
+                assert_eq!(ListStorage::remove(&mut chain, index), model.remove(index));
+            }
+            _ => assert_eq!(ListStorage::pop(&mut chain), model.pop()),
+        }
+        if value % 100 == 0 {
+            assert_matches(&chain, &model);
+        }
+    }
+    assert_matches(&chain, &model);
+    assert!(storage_lens(&chain).iter().all(|&len| len <= 4));
+}
+
+#[test]
+fn pushing_fills_reserved_storages_before_creating_new_ones() {
+    let mut chain = VecChain::new();
+    chain.set_limit(4);
+    for value in 0..6 {
+        ListStorage::push(&mut chain, value);
+    }
+    ListStorage::reserve(&mut chain, 20);
+    let num_storages = chain.num_storages();
+    assert_eq!(num_storages, 7);
+    let mut model = (0..6).collect::<Vec<_>>();
+    // The last reserved storage only has the capacity which was asked for, but it still takes
+    // elements up to the limit
+    for value in 6..28 {
+        ListStorage::push(&mut chain, value);
+        model.push(value);
+        assert_matches(&chain, &model);
+    }
+    assert_eq!(chain.num_storages(), num_storages);
+    ListStorage::push(&mut chain, 28);
+    assert_eq!(chain.num_storages(), num_storages + 1);
+    assert_eq!(ListStorage::get(&chain, 28), Some(&28));
+}
+
+#[test]
+fn pushing_and_popping_at_a_storage_boundary_keeps_the_storage() {
+    let mut chain = chain_of(4, 8);
+    ListStorage::push(&mut chain, 8);
+    assert_eq!(storage_lens(&chain), [4, 4, 1]);
+    let capacity = ListStorage::capacity(&chain);
+    for _ in 0..100 {
+        assert_eq!(ListStorage::pop(&mut chain), Some(8));
+        assert_eq!(storage_lens(&chain), [4, 4, 0]);
+        ListStorage::push(&mut chain, 8);
+        assert_eq!(storage_lens(&chain), [4, 4, 1]);
+        assert_eq!(ListStorage::capacity(&chain), capacity);
+    }
+    assert_matches(&chain, &(0..9).collect::<Vec<_>>());
+    // Removing the last element by index keeps the storage too, but not in the middle
+    ListStorage::remove(&mut chain, 8);
+    assert_eq!(storage_lens(&chain), [4, 4, 0]);
+    for _ in 0..4 {
+        ListStorage::remove(&mut chain, 0);
+    }
+    assert_eq!(storage_lens(&chain), [4, 0]);
+    assert_matches(&chain, &[4, 5, 6, 7]);
+}
+
+#[test]
+fn insert_into_a_full_storage_splits_it() {
+    let mut chain = chain_of(4, 8);
+    assert_eq!(storage_lens(&chain), [4, 4]);
+    ListStorage::insert(&mut chain, 1, 100);
+    assert_eq!(storage_lens(&chain), [3, 2, 4]);
+    ListStorage::insert(&mut chain, 7, 200);
+    assert_eq!(storage_lens(&chain), [3, 2, 3, 2]);
+    assert_matches(&chain, &[0, 100, 1, 2, 3, 4, 5, 200, 6, 7]);
+
+    // Inserting at the end of a full chain starts a new storage instead
+    let mut chain = chain_of(4, 8);
+    ListStorage::insert(&mut chain, 8, 8);
+    assert_eq!(storage_lens(&chain), [4, 4, 1]);
+
+    // No amount of insertions makes a storage grow past the limit
+    let mut chain = chain_of(4, 4);
+    for value in 0..100 {
+        ListStorage::insert(&mut chain, 2, value);
+    }
+    assert_eq!(ListStorage::len(&chain), 104);
+    assert!(storage_lens(&chain).iter().all(|&len| len <= 4));
+}
+
+#[test]
+fn splitting_on_insert_can_be_disabled() {
+    let mut chain = chain_of(4, 8);
+    assert!(chain.splits_on_insert());
+    chain.set_split_on_insert(false);
+    assert!(!chain.splits_on_insert());
+    ListStorage::insert(&mut chain, 1, 100);
+    ListStorage::insert(&mut chain, 1, 100);
+    assert_eq!(storage_lens(&chain), [6, 4]);
+    assert_matches(&chain, &[0, 100, 100, 1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn removal_merges_storages_below_the_low_water_mark() {
+    let mut chain = chain_of(8, 24);
+    assert_eq!(chain.low_water_mark(), 2);
+    for _ in 0..6 {
+        ListStorage::remove(&mut chain, 8);
+    }
+    assert_eq!(storage_lens(&chain), [8, 2, 8]);
+    // Falling below the mark merges the storage with a neighbor which it fits together with
+    ListStorage::remove(&mut chain, 0);
+    ListStorage::remove(&mut chain, 7);
+    assert_eq!(storage_lens(&chain), [8, 8]);
+    assert_matches(
+        &chain,
+        &[1, 2, 3, 4, 5, 6, 7, 15, 16, 17, 18, 19, 20, 21, 22, 23],
+    );
+
+    // Storages which don't fit together with either neighbor are left alone
+    let mut chain = chain_of(8, 24);
+    for _ in 0..7 {
+        ListStorage::remove(&mut chain, 8);
+    }
+    assert_eq!(storage_lens(&chain), [8, 1, 8]);
+
+    // Emptied storages are removed regardless of their neighbors
+    let mut chain = chain_of(4, 12);
+    assert_eq!(chain.low_water_mark(), 1);
+    for _ in 0..4 {
+        ListStorage::remove(&mut chain, 4);
+    }
+    assert_eq!(storage_lens(&chain), [4, 4]);
+}
+
+#[test]
+fn merging_can_be_disabled() {
+    let mut chain = chain_of(8, 24);
+    chain.set_low_water_mark(0);
+    assert_eq!(chain.low_water_mark(), 0);
+    for _ in 0..6 {
+        ListStorage::remove(&mut chain, 8);
+    }
+    ListStorage::remove(&mut chain, 0);
+    ListStorage::remove(&mut chain, 7);
+    assert_eq!(storage_lens(&chain), [7, 1, 8]);
+}
+
+#[test]
+fn low_water_mark_follows_the_limit_unless_set() {
+    let mut chain = VecChain::new();
+    chain.set_limit(64);
+    assert_eq!(chain.low_water_mark(), 16);
+    chain.set_limit(16);
+    assert_eq!(chain.low_water_mark(), 4);
+    chain.set_low_water_mark(10);
+    chain.set_limit(64);
+    assert_eq!(chain.low_water_mark(), 10);
+}
+
+#[test]
+fn elements_cross_storage_boundaries_in_both_directions() {
+    let mut chain = chain_of(4, 10);
+    // Leave an empty storage in the middle and reserved ones at the end
+    chain.set_low_water_mark(0);
+    for _ in 0..4 {
+        ListStorage::remove(&mut chain, 4);
+    }
+    ListStorage::reserve(&mut chain, 8);
+    assert_eq!(storage_lens(&chain), [4, 0, 2, 0, 0]);
+
+    let elements = chain.elements();
+    assert_eq!(elements.len(), 6);
+    assert_eq!(elements.copied().collect::<Vec<_>>(), [0, 1, 2, 3, 8, 9]);
+    assert_eq!(
+        chain.elements().rev().copied().collect::<Vec<_>>(),
+        [9, 8, 3, 2, 1, 0]
+    );
+
+    // Meeting in the middle never yields an element twice, even inside a single storage
+    let mut elements = chain.elements();
+    assert_eq!(elements.next_back(), Some(&9));
+    assert_eq!(elements.next(), Some(&0));
+    assert_eq!(elements.len(), 4);
+    assert_eq!(elements.next_back(), Some(&8));
+    assert_eq!(elements.next_back(), Some(&3));
+    assert_eq!(elements.next(), Some(&1));
+    assert_eq!(elements.next_back(), Some(&2));
+    assert_eq!(elements.len(), 0);
+    assert_eq!(elements.next(), None);
+    assert_eq!(elements.next_back(), None);
+
+    assert_eq!(VecChain::new().elements().next(), None);
+    assert_eq!(VecChain::new().elements().next_back(), None);
+}
+
+#[test]
+fn elements_mut_cross_storage_boundaries() {
+    let mut chain = chain_of(2, 7);
+    let mut elements = chain.elements_mut();
+    assert_eq!(elements.len(), 7);
+    *elements.next_back().unwrap() = 60;
+    for element in elements.by_ref().take(3) {
+        *element += 100;
+    }
+    assert_eq!(elements.len(), 3);
+    for element in elements {
+        *element *= 10;
+    }
+    assert_matches(&chain, &[100, 101, 102, 30, 40, 50, 60]);
+}
+
+/// Collects the elements of any list, which only compiles if the type is a `List`.
+fn collect_list<L: List<Element = u32>>(list: &L) -> Vec<u32> {
+    IntoRefIterator::iter(list).copied().collect()
+}
+
+#[test]
+fn chains_are_lists() {
+    let mut chain = chain_of(2, 5);
+    assert_eq!(collect_list(&chain), [0, 1, 2, 3, 4]);
+    for element in IntoMutIterator::iter_mut(&mut chain) {
+        *element += 1;
+    }
+    assert_eq!(collect_list(&chain), [1, 2, 3, 4, 5]);
+
+    // Chains can be nested, since the storages of a chain only need to be lists
+    let mut nested = Chain::<u32, VecChain, Vec<VecChain>, Vec<usize>>::new();
+    nested.set_limit(4);
+    for value in 0..10 {
+        ListStorage::push(&mut nested, value);
+    }
+    ListStorage::insert(&mut nested, 5, 100);
+    ListStorage::remove(&mut nested, 0);
+    assert_eq!(nested.num_storages(), 4);
+    assert_eq!(ListStorage::get(&nested, 4), Some(&100));
+    assert_eq!(collect_list(&nested), [1, 2, 3, 4, 100, 5, 6, 7, 8, 9]);
+}
+
+/// Creates a chain with the specified limit and elements.
+fn chain_from(limit: usize, elements: &[u32]) -> VecChain {
+    let mut chain = VecChain::new();
+    chain.set_limit(limit);
+    for &element in elements {
+        ListStorage::push(&mut chain, element);
+    }
+    chain
+}
+
+#[test]
+fn split_off_moves_whole_storages() {
+    let mut chain = chain_of(4, 10);
+    chain.set_low_water_mark(3);
+    chain.set_split_on_insert(false);
+    let tail = chain.split_off(6);
+    assert_eq!(storage_lens(&chain), [4, 2]);
+    assert_eq!(storage_lens(&tail), [2, 2]);
+    assert_matches(&chain, &[0, 1, 2, 3, 4, 5]);
+    assert_matches(&tail, &[6, 7, 8, 9]);
+    // The new chain has the same settings
+    assert_eq!(tail.limit(), 4);
+    assert_eq!(tail.low_water_mark(), 3);
+    assert!(!tail.splits_on_insert());
+
+    // Splitting at a storage boundary doesn't move any elements
+    let mut chain = chain_of(4, 10);
+    let tail = chain.split_off(4);
+    assert_eq!(storage_lens(&chain), [4]);
+    assert_eq!(storage_lens(&tail), [4, 2]);
+
+    let mut chain = chain_of(4, 10);
+    let tail = chain.split_off(10);
+    assert_eq!(ListStorage::len(&tail), 0);
+    assert_matches(&chain, &(0..10).collect::<Vec<_>>());
+    let tail = chain.split_off(0);
+    assert_eq!(ListStorage::len(&chain), 0);
+    assert_eq!(storage_lens(&tail), [4, 4, 2]);
+    // Both halves are still usable
+    ListStorage::push(&mut chain, 100);
+    assert_matches(&chain, &[100]);
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn split_off_past_the_end_panics() {
+    let _ = chain_of(4, 10).split_off(11);
+}
+
+#[test]
+fn append_moves_whole_storages() {
+    let mut chain = chain_of(4, 10);
+    let mut other = chain_from(4, &[100, 101, 102]);
+    chain.append(&mut other);
+    assert_eq!(storage_lens(&chain), [4, 4, 2, 3]);
+    assert_matches(&chain, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 100, 101, 102]);
+    assert_eq!(ListStorage::len(&other), 0);
+    assert_eq!(other.num_storages(), 0);
+    ListStorage::push(&mut other, 5);
+    assert_matches(&other, &[5]);
+
+    // Storages below the low-water mark are merged across the boundary
+    let mut chain = chain_of(8, 9);
+    chain.append(&mut chain_from(8, &[100, 101, 102]));
+    assert_eq!(storage_lens(&chain), [8, 4]);
+    assert_matches(&chain, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 100, 101, 102]);
+
+    // Reserved storages don't end up in the middle
+    let mut chain = chain_of(4, 5);
+    ListStorage::reserve(&mut chain, 8);
+    assert_eq!(storage_lens(&chain), [4, 1, 0, 0]);
+    chain.append(&mut chain_from(4, &[100, 101]));
+    assert_eq!(storage_lens(&chain), [4, 1, 2]);
+    assert_matches(&chain, &[0, 1, 2, 3, 4, 100, 101]);
+
+    let mut chain = VecChain::new();
+    chain.append(&mut chain_of(4, 5));
+    assert_matches(&chain, &[0, 1, 2, 3, 4]);
+    chain.append(&mut VecChain::new());
+    assert_matches(&chain, &[0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn splice_chain_replaces_ranges() {
+    let mut chain = chain_of(4, 10);
+    let removed = chain.splice_chain(2..8, chain_from(4, &[100, 101, 102]));
+    assert_matches(&removed, &[2, 3, 4, 5, 6, 7]);
+    assert_matches(&chain, &[0, 1, 100, 101, 102, 8, 9]);
+
+    // An empty range only inserts
+    let removed = chain.splice_chain(7..7, chain_from(4, &[200]));
+    assert_eq!(ListStorage::len(&removed), 0);
+    assert_matches(&chain, &[0, 1, 100, 101, 102, 8, 9, 200]);
+    let removed = chain.splice_chain(..=1, VecChain::new());
+    assert_matches(&removed, &[0, 1]);
+    let removed = chain.splice_chain(.., chain_of(4, 2));
+    assert_matches(&removed, &[100, 101, 102, 8, 9, 200]);
+    assert_matches(&chain, &[0, 1]);
+}
+
+#[test]
+#[should_panic(expected = "range out of bounds")]
+fn splice_chain_past_the_end_panics() {
+    let _ = chain_of(4, 10).splice_chain(5..11, VecChain::new());
+}
+
+#[test]
+#[should_panic(expected = "range start is greater than range end")]
+fn splice_chain_of_a_reversed_range_panics() {
+    #[allow(clippy::reversed_empty_ranges)]
+    let _ = chain_of(4, 10).splice_chain(5..4, VecChain::new());
+}
+
+#[test]
+fn truncate_empties_the_storages_after_the_new_end() {
+    let mut chain = chain_of(4, 14);
+    ListStorage::truncate(&mut chain, 6);
+    assert_eq!(storage_lens(&chain), [4, 2, 0, 0]);
+    assert_matches(&chain, &[0, 1, 2, 3, 4, 5]);
+    // Truncating at a boundary or past the end
+    ListStorage::truncate(&mut chain, 10);
+    assert_matches(&chain, &[0, 1, 2, 3, 4, 5]);
+    ListStorage::truncate(&mut chain, 4);
+    assert_eq!(storage_lens(&chain), [4, 0, 0, 0]);
+    assert_matches(&chain, &[0, 1, 2, 3]);
+
+    // The emptied storages are filled again before new ones are created
+    for value in 4..16 {
+        ListStorage::push(&mut chain, value);
+    }
+    assert_eq!(storage_lens(&chain), [4, 4, 4, 4]);
+    assert_matches(&chain, &(0..16).collect::<Vec<_>>());
+
+    ListStorage::truncate(&mut chain, 0);
+    assert_matches(&chain, &[]);
+    assert_eq!(ListStorage::pop(&mut chain), None);
+    ListStorage::push(&mut chain, 100);
+    assert_eq!(storage_lens(&chain), [1, 0, 0, 0]);
+}
+
+#[test]
+fn shrink_to_fit_removes_empty_storages_only() {
+    let mut chain = chain_of(4, 14);
+    chain.set_low_water_mark(0);
+    for _ in 0..4 {
+        ListStorage::remove(&mut chain, 4);
+    }
+    ListStorage::truncate(&mut chain, 8);
+    assert_eq!(storage_lens(&chain), [4, 0, 4, 0]);
+    ListStorage::shrink_to_fit(&mut chain);
+    assert_eq!(storage_lens(&chain), [4, 4]);
+    assert_matches(&chain, &[0, 1, 2, 3, 8, 9, 10, 11]);
+    assert_eq!(ListStorage::capacity(&chain), 8);
+
+    let mut chain = chain_of(4, 3);
+    ListStorage::truncate(&mut chain, 0);
+    ListStorage::shrink_to_fit(&mut chain);
+    assert_eq!(chain.num_storages(), 0);
+    assert_eq!(ListStorage::capacity(&chain), 0);
+    ListStorage::push(&mut chain, 100);
+    assert_matches(&chain, &[100]);
+}
+
+#[test]
+fn reserve_only_creates_storages_when_needed() {
+    let mut chain = chain_of(4, 2);
+    ListStorage::reserve(&mut chain, 0);
+    ListStorage::reserve(&mut chain, 2);
+    assert_eq!(chain.num_storages(), 1);
+    ListStorage::reserve(&mut chain, 3);
+    assert_eq!(storage_lens(&chain), [2, 0]);
+    assert!(ListStorage::capacity(&chain) >= 5);
+    ListStorage::reserve(&mut chain, 10);
+    assert_eq!(storage_lens(&chain), [2, 0, 0]);
+    assert!(ListStorage::capacity(&chain) >= 12);
+    assert_matches(&chain, &[0, 1]);
+
+    let mut chain = VecChain::new();
+    ListStorage::reserve(&mut chain, 0);
+    assert_eq!(chain.num_storages(), 0);
+    let chain = <VecChain as ListStorage>::with_capacity(5);
+    assert_eq!(ListStorage::len(&chain), 0);
+    assert!(ListStorage::capacity(&chain) >= 5);
+}
+
+#[test]
+fn shiftfix_methods_keep_the_length_in_sync() {
+    type Element = DummyMoveFix<u32>;
+    let mut chain = Chain::<Element, Vec<Element>, Vec<Vec<Element>>, Vec<usize>>::new();
+    chain.set_limit(4);
+    let mut model = Vec::new();
+    for value in 0..20 {
+        let index = (value as usize * 7) % (model.len() + 1);
+        ListStorage::insert_and_shiftfix(&mut chain, index, value.into());
+        model.insert(index, value);
+        assert_eq!(ListStorage::len(&chain), model.len());
+    }
+    for step in 0..15 {
+        let index = (step * 5) % model.len();
+        assert_eq!(
+            ListStorage::remove_and_shiftfix(&mut chain, index).0,
+            model.remove(index)
+        );
+        assert_eq!(ListStorage::len(&chain), model.len());
+    }
+    assert_eq!(chain.elements().map(|x| x.0).collect::<Vec<_>>(), model);
+    assert_eq!(ListStorage::get(&chain, model.len()), None);
+}
+
+/// Points to one of the roots of the chain by its index in the whole chain.
+struct Node {
+    root: u32,
+    target: usize,
+}
+impl MoveFix for Node {
+    unsafe fn fix_shift<S>(storage: &mut S, shifted_from: usize, shifted_by: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        for index in 0..storage.len() {
+            let node = storage.get_mut(index).unwrap();
+            if node.target >= shifted_from {
+                node.target = (node.target as isize + shifted_by.get()) as usize;
+            }
+        }
+    }
+    unsafe fn fix_move<S>(_: &mut S, _: usize, _: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        unreachable!("moving between storages keeps the index in the whole chain")
+    }
+}
+
+type NodeChain = Chain<Node, Vec<Node>, Vec<Vec<Node>>, Vec<usize>>;
+
+/// Checks that every node points to its root, returning the indices of the nodes which are not roots.
+fn check_nodes(chain: &NodeChain) -> Vec<usize> {
+    let mut non_roots = Vec::new();
+    for (index, node) in chain.elements().enumerate() {
+        let target = ListStorage::get(chain, node.target).unwrap();
+        assert_eq!(target.root, node.root);
+        assert_eq!(target.target, node.target);
+        if node.target != index {
+            non_roots.push(index);
+        }
+    }
+    non_roots
+}
+
+#[test]
+fn shiftfix_methods_notify_in_whole_chain_coordinates() {
+    let mut rng = Lcg(7);
+    let mut chain = NodeChain::new();
+    chain.set_limit(4);
+    // The roots point to themselves
+    for root in 0..4 {
+        ListStorage::push(
+            &mut chain,
+            Node {
+                root,
+                target: root as usize,
+            },
+        );
+    }
+    for step in 0..300 {
+        let non_roots = check_nodes(&chain);
+        if step % 3 == 2 && !non_roots.is_empty() {
+            let index = non_roots[rng.below(non_roots.len())];
+            ListStorage::remove_and_shiftfix(&mut chain, index);
+        } else {
+            // Point to a root by its index before the insertion, which the shift then fixes
+            let root = rng.below(4) as u32;
+            let target = chain
+                .elements()
+                .enumerate()
+                .find(|(index, node)| node.root == root && node.target == *index)
+                .unwrap()
+                .0;
+            let index = rng.below(ListStorage::len(&chain) + 1);
+            ListStorage::insert_and_shiftfix(&mut chain, index, Node { root, target });
+        }
+    }
+    assert_eq!(check_nodes(&chain).len(), ListStorage::len(&chain) - 4);
+    assert!(chain.num_storages() > 4);
+}
+
+/// Points to another element by its index, relying on the default implementation of `fix_range_shift`.
+#[derive(Debug, PartialEq)]
+struct Link(usize);
+impl MoveFix for Link {
+    unsafe fn fix_shift<S>(storage: &mut S, shifted_from: usize, shifted_by: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        for index in 0..storage.len() {
+            let link = storage.get_mut(index).unwrap();
+            if link.0 >= shifted_from {
+                link.0 = (link.0 as isize + shifted_by.get()) as usize;
+            }
+        }
+    }
+    unsafe fn fix_move<S>(storage: &mut S, previous_index: usize, current_index: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        for index in 0..storage.len() {
+            let link = storage.get_mut(index).unwrap();
+            if link.0 == previous_index {
+                link.0 = current_index;
+            }
+        }
+    }
+}
+
+type LinkChain = Chain<Link, Vec<Link>, Vec<Vec<Link>>, Vec<usize>>;
+
+fn link_chain(targets: &[usize]) -> LinkChain {
+    let mut chain = LinkChain::new();
+    chain.set_limit(2);
+    for &target in targets {
+        ListStorage::push(&mut chain, Link(target));
+    }
+    chain
+}
+
+fn targets(chain: &LinkChain) -> Vec<usize> {
+    chain.elements().map(|link| link.0).collect()
+}
+
+#[test]
+fn default_range_shift_moves_every_element_once() {
+    // Growing the range shifts the elements after it to the right
+    let mut chain = link_chain(&[0, 1, 2, 3, 4]);
+    let removed = chain.splice_chain_and_shiftfix(1..2, link_chain(&[0, 0, 0]));
+    assert_eq!(targets(&removed), [1]);
+    assert_eq!(targets(&chain), [0, 0, 0, 0, 4, 5, 6]);
+
+    // Shrinking it shifts them to the left
+    let mut chain = link_chain(&[4, 1, 2, 3, 4, 0]);
+    chain.splice_chain_and_shiftfix(1..4, link_chain(&[0]));
+    assert_eq!(targets(&chain), [2, 0, 2, 0]);
+
+    // Ranges which don't extend to the end are fixed element by element, without moving an
+    // element onto one which is yet to be moved
+    let mut vec = vec![Link(0), Link(0), Link(1), Link(2), Link(4)];
+    unsafe {
+        Link::fix_range_shift(&mut vec, 1..3, NonZeroIsize::new(1).unwrap());
+    }
+    assert_eq!(vec, [Link(0), Link(0), Link(2), Link(3), Link(4)]);
+    let mut vec = vec![Link(0), Link(2), Link(3), Link(4), Link(4)];
+    unsafe {
+        Link::fix_range_shift(&mut vec, 2..4, NonZeroIsize::new(-1).unwrap());
+    }
+    assert_eq!(vec, [Link(0), Link(1), Link(2), Link(4), Link(4)]);
+}
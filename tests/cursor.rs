@@ -0,0 +1,42 @@
+#![cfg(feature = "alloc")]
+
+use granite::{DummyMoveFix, IntoRefIterator, ListCursorMut, ListStorage, SparseVec};
+
+type Element = DummyMoveFix<u32>;
+
+/// Creates a storage with the specified amount of elements, each equal to its index, and punches holes at the specified indices.
+fn sparse_with_holes(len: u32, holes: &[usize]) -> SparseVec<Element> {
+    let mut storage: SparseVec<Element> = ListStorage::new();
+    for value in 0..len {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+#[test]
+fn removing_a_hole_returns_none() {
+    let mut storage = sparse_with_holes(5, &[1]);
+    let mut cursor = ListCursorMut::new(&mut storage, 1);
+    assert!(cursor.current().is_none());
+    assert_eq!(cursor.remove_current(), None);
+    assert_eq!(cursor.remove_current_and_shiftfix(), None);
+    assert_eq!(cursor.index(), Some(1));
+
+    // Removing with shiftfix leaves a hole behind, which can't be removed again
+    cursor.move_next();
+    assert_eq!(cursor.remove_current_and_shiftfix(), Some(2.into()));
+    assert!(cursor.current().is_none());
+    assert_eq!(cursor.remove_current_and_shiftfix(), None);
+    assert_eq!(cursor.remove_current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(3.into()));
+    assert_eq!(
+        IntoRefIterator::iter(&storage)
+            .map(|x| x.0)
+            .collect::<Vec<_>>(),
+        [0, 4]
+    );
+}
@@ -0,0 +1,85 @@
+//! Runs with and without the `alloc` feature, since without it the moves are reported in fixed-size batches while the storage is being defragmented.
+#![cfg(feature = "arrayvec")]
+
+use arrayvec::ArrayVec;
+use core::{
+    num::NonZeroIsize,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use granite::{ListStorage, MoveFix, SparseStorage, SparseStorageSlot as Slot};
+
+type Storage = SparseStorage<Tracked, ArrayVec<[Slot<Tracked>; 256]>, ArrayVec<[usize; 8]>>;
+
+static FIX_MOVES_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// An element which knows its own index and checks the storage every time it's notified about moves. Two words big, so that the word storage only has to hold the occupancy bitmap.
+#[derive(Debug)]
+struct Tracked {
+    index: usize,
+    moves: usize,
+}
+impl MoveFix for Tracked {
+    unsafe fn fix_shift<S>(_: &mut S, _: usize, _: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+    }
+    unsafe fn fix_move<S>(storage: &mut S, previous_index: usize, current_index: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        let element = storage.get_mut(current_index).unwrap();
+        assert_eq!(element.index, previous_index);
+        element.index = current_index;
+        element.moves += 1;
+    }
+    unsafe fn fix_moves<S>(storage: &mut S, moves: &[(usize, usize)])
+    where
+        S: ListStorage<Element = Self>,
+    {
+        FIX_MOVES_CALLS.fetch_add(1, Ordering::Relaxed);
+        for &(previous_index, current_index) in moves {
+            Self::fix_move(storage, previous_index, current_index);
+        }
+        // Every move which was performed so far has been reported, and the slots which the
+        // elements were moved out of are gone
+        let len = storage.len();
+        assert!(storage.contains_index(len - 1));
+        for index in 0..len {
+            if storage.contains_index(index) {
+                assert_eq!(storage.get(index).unwrap().index, index);
+            }
+        }
+    }
+}
+
+#[test]
+fn hooks_see_a_consistent_storage_between_batches() {
+    let mut storage = Tracked::new_storage(200);
+    for index in 0..100 {
+        storage.remove_and_shiftfix(index);
+    }
+    assert_eq!(storage.num_holes(), 100);
+    storage.defragment_and_fix();
+    assert!(storage.is_dense());
+    assert_eq!(storage.len(), 100);
+    for index in 0..100 {
+        let element = storage.get(index).unwrap();
+        assert_eq!(element.index, index);
+        // Every hole was at the start, so every element was moved into one exactly once
+        assert_eq!(element.moves, 1);
+    }
+    // 100 moves are reported in batches of 64 without an allocator
+    let expected_calls = if cfg!(feature = "alloc") { 1 } else { 2 };
+    assert_eq!(FIX_MOVES_CALLS.load(Ordering::Relaxed), expected_calls);
+}
+
+impl Tracked {
+    fn new_storage(len: usize) -> Storage {
+        let mut storage = Storage::new();
+        for index in 0..len {
+            storage.add(Self { index, moves: 0 });
+        }
+        storage
+    }
+}
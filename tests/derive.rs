@@ -0,0 +1,179 @@
+#![cfg(all(feature = "derive", feature = "alloc"))]
+
+use granite::{IntoRefIterator, ListStorage, MoveFix, SparseVec};
+
+#[derive(MoveFix, Debug, PartialEq)]
+struct Links {
+    #[movefix(index)]
+    prev: Option<usize>,
+    #[movefix(index)]
+    next: Option<usize>,
+}
+
+#[derive(MoveFix, Debug, PartialEq)]
+enum Node {
+    Leaf(u32, #[movefix(index)] usize),
+    Branch {
+        #[movefix(index)]
+        children: [usize; 2],
+        #[movefix(index)]
+        links: Links,
+        #[movefix(index)]
+        extra: Vec<Option<usize>>,
+    },
+    Empty,
+}
+
+/// Points to the next element of a singly linked list by its index.
+#[derive(MoveFix, Debug, Clone, Copy, PartialEq)]
+struct Next(u32, #[movefix(index)] Option<usize>);
+
+/// Creates a storage of a linked list in which every element points to the one after it.
+fn linked_list(len: u32) -> SparseVec<Next> {
+    let mut storage: SparseVec<Next> = ListStorage::new();
+    for value in 0..len {
+        let next = if value + 1 < len {
+            Some(value as usize + 1)
+        } else {
+            None
+        };
+        ListStorage::add(&mut storage, Next(value, next));
+    }
+    storage
+}
+
+/// Follows the links starting from the specified index, collecting the values.
+fn walk(storage: &SparseVec<Next>, start: usize) -> Vec<u32> {
+    let mut values = Vec::new();
+    let mut next = Some(start);
+    while let Some(index) = next {
+        let element = ListStorage::get(storage, index).unwrap();
+        values.push(element.0);
+        next = element.1;
+    }
+    values
+}
+
+/// Removes the element at the specified index from the linked list, leaving a hole behind.
+fn unlink(storage: &mut SparseVec<Next>, index: usize) {
+    let next = ListStorage::get(storage, index).unwrap().1;
+    ListStorage::get_mut(storage, index - 1).unwrap().1 = next;
+    storage.remove_and_shiftfix(index);
+}
+
+#[test]
+fn marked_fields_of_all_kinds_are_fixed() {
+    let mut list = vec![
+        Node::Leaf(0, 3),
+        Node::Branch {
+            children: [0, 3],
+            links: Links {
+                prev: Some(3),
+                next: None,
+            },
+            extra: vec![Some(3), None, Some(1)],
+        },
+        Node::Empty,
+        Node::Leaf(1, 0),
+    ];
+    ListStorage::insert_and_shiftfix(&mut list, 1, Node::Empty);
+    assert_eq!(list[0], Node::Leaf(0, 4));
+    assert_eq!(
+        list[2],
+        Node::Branch {
+            children: [0, 4],
+            links: Links {
+                prev: Some(4),
+                next: None,
+            },
+            extra: vec![Some(4), None, Some(2)],
+        }
+    );
+    assert_eq!(list[4], Node::Leaf(1, 0));
+    ListStorage::remove_and_shiftfix(&mut list, 1);
+    assert_eq!(list[0], Node::Leaf(0, 3));
+    assert_eq!(
+        list[1],
+        Node::Branch {
+            children: [0, 3],
+            links: Links {
+                prev: Some(3),
+                next: None,
+            },
+            extra: vec![Some(3), None, Some(1)],
+        }
+    );
+}
+
+#[derive(MoveFix, Debug, PartialEq)]
+struct Arrays {
+    #[movefix(index)]
+    nested: Option<[Option<usize>; 3]>,
+    #[movefix(index)]
+    pairs: Vec<[usize; 2]>,
+    #[movefix(index)]
+    long: [usize; 40],
+}
+
+#[test]
+fn arrays_are_fixed_at_any_depth() {
+    let mut list = vec![Arrays {
+        nested: Some([Some(0), None, Some(1)]),
+        pairs: vec![[0, 1], [1, 0]],
+        long: [1; 40],
+    }];
+    ListStorage::insert_and_shiftfix(&mut list, 0, Arrays {
+        nested: None,
+        pairs: Vec::new(),
+        long: [0; 40],
+    });
+    assert_eq!(
+        list[1],
+        Arrays {
+            nested: Some([Some(1), None, Some(2)]),
+            pairs: vec![[1, 2], [2, 1]],
+            long: [2; 40],
+        }
+    );
+}
+
+#[test]
+fn holes_are_skipped_when_shifting() {
+    let mut storage = linked_list(6);
+    unlink(&mut storage, 2);
+    assert_eq!(storage.num_holes(), 1);
+    ListStorage::insert_and_shiftfix(&mut storage, 0, Next(100, Some(0)));
+    assert_eq!(walk(&storage, 0), [100, 0, 1, 3, 4, 5]);
+    assert_eq!(storage.num_holes(), 1);
+}
+
+#[test]
+fn defragmentation_keeps_the_links_intact() {
+    let mut storage = linked_list(40);
+    for index in (1..39).step_by(3) {
+        unlink(&mut storage, index);
+    }
+    let before = walk(&storage, 0);
+    storage.defragment_and_fix();
+    assert!(storage.is_dense());
+    assert_eq!(walk(&storage, 0), before);
+    assert_eq!(IntoRefIterator::iter(&storage).count(), before.len());
+}
+
+#[test]
+fn moves_are_applied_once_in_any_order() {
+    // Every element is moved onto the index which another one was moved from
+    let mut list = vec![
+        Next(0, Some(3)),
+        Next(1, Some(0)),
+        Next(2, Some(5)),
+        Next(3, Some(1)),
+    ];
+    unsafe {
+        Next::fix_moves(&mut list, &[(5, 3), (0, 2), (3, 0), (1, 5)]);
+    }
+    assert_eq!(
+        list.iter().map(|element| element.1).collect::<Vec<_>>(),
+        [Some(0), Some(2), Some(3), Some(5)]
+    );
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "alloc")]
+
+use granite::{DummyMoveFix, HoleReuse, IntoRefIterator, ListStorage, SparseVec};
+
+/// Big enough for its holes to be linked into the hole list, which the FIFO and LIFO strategies rely on.
+type Element = DummyMoveFix<[usize; 2]>;
+
+fn element(value: usize) -> Element {
+    [value, 0].into()
+}
+
+/// Creates a storage with the specified amount of elements, each equal to its index, and punches holes at the specified indices in the specified order.
+fn with_holes(len: usize, holes: &[usize]) -> SparseVec<Element> {
+    let mut storage: SparseVec<Element> = ListStorage::new();
+    for value in 0..len {
+        ListStorage::add(&mut storage, element(value));
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+/// Adds an element for every hole in the storage and returns the indices at which they ended up.
+fn fill_holes(storage: &mut SparseVec<Element>) -> Vec<usize> {
+    let mut indices = Vec::new();
+    for _ in 0..storage.num_holes() {
+        indices.push(ListStorage::add(storage, element(100)));
+    }
+    assert!(storage.is_dense());
+    indices
+}
+
+fn values(storage: &SparseVec<Element>) -> Vec<usize> {
+    IntoRefIterator::iter(storage).map(|x| x.0[0]).collect()
+}
+
+#[test]
+fn fifo_fills_the_earliest_hole_first() {
+    let mut storage = with_holes(10, &[5, 1, 8, 3]);
+    assert_eq!(storage.hole_reuse(), HoleReuse::Fifo);
+    assert_eq!(fill_holes(&mut storage), [5, 1, 8, 3]);
+    assert_eq!(ListStorage::add(&mut storage, element(100)), 10);
+}
+
+#[test]
+fn lifo_fills_the_latest_hole_first() {
+    let mut storage = with_holes(10, &[5, 1, 8, 3]);
+    storage.set_hole_reuse(HoleReuse::Lifo);
+    assert_eq!(fill_holes(&mut storage), [3, 8, 1, 5]);
+    assert_eq!(ListStorage::add(&mut storage, element(100)), 10);
+}
+
+#[test]
+fn lowest_index_fills_the_lowest_hole_first() {
+    let mut storage = with_holes(10, &[5, 1, 8, 3]);
+    storage.set_hole_reuse(HoleReuse::LowestIndex);
+    // Holes punched after switching are taken into account too
+    storage.remove_and_shiftfix(0);
+    assert_eq!(fill_holes(&mut storage), [0, 1, 3, 5, 8]);
+    assert_eq!(ListStorage::add(&mut storage, element(100)), 10);
+}
+
+#[test]
+fn switching_strategies_keeps_every_hole() {
+    let mut storage = with_holes(10, &[5, 1, 8, 3]);
+    storage.set_hole_reuse(HoleReuse::Lifo);
+    assert_eq!(ListStorage::add(&mut storage, element(100)), 3);
+    storage.set_hole_reuse(HoleReuse::LowestIndex);
+    assert_eq!(ListStorage::add(&mut storage, element(100)), 1);
+    storage.set_hole_reuse(HoleReuse::Fifo);
+    assert_eq!(fill_holes(&mut storage), [5, 8]);
+    assert_eq!(values(&storage), [0, 100, 2, 100, 4, 100, 6, 7, 100, 9]);
+}
+
+/// Too small for the links, which are kept in the link table of the storage instead once a strategy other than `LowestIndex` is selected.
+type Small = DummyMoveFix<u32>;
+
+/// Same as `with_holes`, but selects the specified strategy before punching the holes, so that the link table records their order.
+fn small_with_holes(len: u32, holes: &[usize], strategy: HoleReuse) -> SparseVec<Small> {
+    let mut storage: SparseVec<Small> = ListStorage::new();
+    storage.set_hole_reuse(strategy);
+    for value in 0..len {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+#[test]
+fn small_elements_follow_every_strategy() {
+    let expected = [
+        (HoleReuse::Fifo, [5, 1, 8, 3]),
+        (HoleReuse::Lifo, [3, 8, 1, 5]),
+        (HoleReuse::LowestIndex, [1, 3, 5, 8]),
+    ];
+    for &(strategy, order) in &expected {
+        let mut storage = small_with_holes(10, &[5, 1, 8, 3], strategy);
+        for &hole in &order {
+            assert_eq!(ListStorage::add(&mut storage, 100.into()), hole);
+        }
+        assert!(storage.is_dense());
+        assert_eq!(ListStorage::add(&mut storage, 100.into()), 10);
+    }
+}
+
+#[test]
+fn small_elements_keep_the_order_across_switches() {
+    let mut storage = small_with_holes(10, &[5, 1, 8, 3], HoleReuse::Lifo);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 3);
+    storage.set_hole_reuse(HoleReuse::LowestIndex);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 1);
+    // Cutting off the end keeps the holes before it, and holes punched afterwards go last
+    ListStorage::truncate(&mut storage, 9);
+    storage.remove_and_shiftfix(0);
+    storage.set_hole_reuse(HoleReuse::Fifo);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 5);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 8);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 0);
+    assert!(storage.is_dense());
+    let values = IntoRefIterator::iter(&storage).map(|x| x.0).collect::<Vec<_>>();
+    assert_eq!(values, [100, 100, 2, 100, 4, 100, 6, 7, 100]);
+}
+
+#[test]
+fn small_elements_fill_the_lowest_hole_by_default() {
+    let mut storage: SparseVec<Small> = ListStorage::new();
+    assert_eq!(storage.hole_reuse(), HoleReuse::LowestIndex);
+    for value in 0..10 {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for &index in &[5, 1, 8, 3] {
+        storage.remove_and_shiftfix(index);
+    }
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 1);
+    // The order in which the remaining holes were punched was never recorded, so they're linked
+    // in the order of their indices
+    storage.set_hole_reuse(HoleReuse::Lifo);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 8);
+    storage.remove_and_shiftfix(0);
+    storage.set_hole_reuse(HoleReuse::Fifo);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 3);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 5);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 0);
+    assert!(storage.is_dense());
+}
@@ -0,0 +1,81 @@
+#![cfg(all(feature = "rayon", feature = "alloc"))]
+
+use rayon::iter::ParallelIterator;
+use std::collections::VecDeque;
+use granite::{Chain, DummyMoveFix, IntoParMutIterator, IntoParRefIterator, ListStorage, SparseVec};
+
+#[test]
+fn dense_lists_are_iterated_in_parallel() {
+    let mut vec = (0..10_000_u64).collect::<Vec<_>>();
+    IntoParMutIterator::par_elements_mut(&mut vec).for_each(|x| *x *= 2);
+    let sum: u64 = IntoParRefIterator::par_elements(&vec).sum();
+    assert_eq!(sum, (0..10_000).map(|x| x * 2).sum());
+
+    let mut deque = (0..10_000_u64).collect::<VecDeque<_>>();
+    // Make the deque wrap around its buffer
+    for _ in 0..100 {
+        let x = deque.pop_front().unwrap();
+        deque.push_back(x);
+    }
+    IntoParMutIterator::par_elements_mut(&mut deque).for_each(|x| *x += 1);
+    let collected: Vec<u64> = IntoParRefIterator::par_elements(&deque).copied().collect();
+    assert_eq!(collected, deque.iter().copied().collect::<Vec<_>>());
+    assert_eq!(collected[0], 101);
+}
+
+#[test]
+fn sparse_storages_skip_holes_in_parallel() {
+    let mut storage: SparseVec<DummyMoveFix<u64>> = ListStorage::new();
+    for value in 0..10_000 {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for index in (0..10_000).filter(|index| index % 3 != 0) {
+        storage.remove_and_shiftfix(index);
+    }
+    assert_eq!(
+        IntoParRefIterator::par_elements(&storage).count(),
+        (0..10_000).step_by(3).count()
+    );
+    IntoParMutIterator::par_elements_mut(&mut storage).for_each(|x| x.0 += 1);
+    let collected: Vec<u64> = IntoParRefIterator::par_elements(&storage)
+        .map(|x| x.0)
+        .collect();
+    assert_eq!(
+        collected,
+        (0..10_000).step_by(3).map(|x| x + 1).collect::<Vec<_>>()
+    );
+
+    // A storage of holes only
+    let mut storage: SparseVec<DummyMoveFix<u64>> = ListStorage::new();
+    for value in 0..100 {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for index in 0..100 {
+        storage.remove_and_shiftfix(index);
+    }
+    assert_eq!(IntoParRefIterator::par_elements(&storage).count(), 0);
+}
+
+#[test]
+fn chains_are_iterated_in_parallel_by_storage() {
+    type VecChain = Chain<u64, Vec<u64>, Vec<Vec<u64>>, Vec<usize>>;
+    let mut chain = VecChain::new();
+    chain.set_limit(64);
+    for value in 0..10_000 {
+        ListStorage::push(&mut chain, value);
+    }
+    // Empty storages in the middle and at the end are skipped
+    chain.set_low_water_mark(0);
+    for _ in 0..64 {
+        ListStorage::remove(&mut chain, 128);
+    }
+    ListStorage::reserve(&mut chain, 1000);
+    chain.par_elements_mut().for_each(|x| *x *= 3);
+    let collected: Vec<u64> = IntoParRefIterator::par_elements(&chain).copied().collect();
+    let expected = (0..128)
+        .chain(192..10_000)
+        .map(|x| x * 3)
+        .collect::<Vec<_>>();
+    assert_eq!(collected, expected);
+    assert_eq!(chain.par_elements().count(), ListStorage::len(&chain));
+}
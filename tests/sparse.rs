@@ -0,0 +1,837 @@
+#![cfg(feature = "alloc")]
+
+use core::{cell::Cell, num::NonZeroIsize};
+use granite::{
+    DefragPolicy, DummyMoveFix, HoleReuse, IntoMutIterator, IntoRefIterator, ListStorage, MoveFix,
+    SparseStorageSlotRef as SlotRef, SparseVec,
+};
+
+type Element = DummyMoveFix<u32>;
+
+/// Creates a storage with the specified amount of elements, each equal to its index, and punches holes at the specified indices.
+fn with_holes(len: u32, holes: &[usize]) -> SparseVec<Element> {
+    let mut storage: SparseVec<Element> = ListStorage::new();
+    for value in 0..len {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+/// Same as `with_holes`, but switches to the `Fifo` hole reuse strategy first, which keeps the links of the holes in the link table since the elements are too small for them.
+fn table_with_holes(len: u32, holes: &[usize]) -> SparseVec<Element> {
+    let mut storage: SparseVec<Element> = ListStorage::new();
+    storage.set_hole_reuse(HoleReuse::Fifo);
+    for value in 0..len {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+fn values(storage: &SparseVec<Element>) -> Vec<u32> {
+    IntoRefIterator::iter(storage).map(|x| x.0).collect()
+}
+
+/// An element big enough for its holes to be linked into the hole list, which the FIFO and LIFO hole reuse strategies rely on.
+type Linked = DummyMoveFix<[usize; 2]>;
+
+fn linked(value: usize) -> Linked {
+    [value, 0].into()
+}
+
+/// Same as `with_holes`, but for elements which keep the hole list.
+fn linked_with_holes(len: usize, holes: &[usize]) -> SparseVec<Linked> {
+    let mut storage: SparseVec<Linked> = ListStorage::new();
+    for value in 0..len {
+        ListStorage::add(&mut storage, linked(value));
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+fn linked_values(storage: &SparseVec<Linked>) -> Vec<usize> {
+    IntoRefIterator::iter(storage).map(|x| x.0[0]).collect()
+}
+
+#[test]
+fn iteration_skips_holes() {
+    let storage = with_holes(8, &[0, 3, 4, 7]);
+    assert_eq!(values(&storage), [1, 2, 5, 6]);
+    let iter = IntoRefIterator::iter(&storage);
+    assert_eq!(iter.len(), 4);
+    let indexed = iter.indexed().map(|(i, x)| (i, x.0)).collect::<Vec<_>>();
+    assert_eq!(indexed, [(1, 1), (2, 2), (5, 5), (6, 6)]);
+    let reversed = IntoRefIterator::iter(&storage)
+        .indexed()
+        .rev()
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    assert_eq!(reversed, [6, 5, 2, 1]);
+
+    // Meeting in the middle never yields an element twice
+    let mut iter = IntoRefIterator::iter(&storage);
+    assert_eq!(iter.next().map(|x| x.0), Some(1));
+    assert_eq!(iter.next_back().map(|x| x.0), Some(6));
+    assert_eq!(iter.next_back().map(|x| x.0), Some(5));
+    assert_eq!(iter.next().map(|x| x.0), Some(2));
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+}
+
+#[test]
+fn iteration_over_holes_only() {
+    let storage = with_holes(3, &[0, 1, 2]);
+    assert_eq!(IntoRefIterator::iter(&storage).len(), 0);
+    assert!(IntoRefIterator::iter(&storage).next().is_none());
+    assert!(IntoRefIterator::iter(&storage).next_back().is_none());
+}
+
+#[test]
+fn mutable_iteration_skips_holes() {
+    let mut storage = with_holes(6, &[1, 4]);
+    for element in IntoMutIterator::iter_mut(&mut storage) {
+        element.0 *= 10;
+    }
+    assert_eq!(values(&storage), [0, 20, 30, 50]);
+    let indexed = IntoMutIterator::iter_mut(&mut storage)
+        .indexed()
+        .rev()
+        .map(|(i, x)| (i, x.0))
+        .collect::<Vec<_>>();
+    assert_eq!(indexed, [(5, 50), (3, 30), (2, 20), (0, 0)]);
+    assert_eq!(storage.num_holes(), 2);
+}
+
+/// An element which knows its own index and checks that every move it's notified about starts there.
+#[derive(Debug)]
+struct Tracked {
+    value: u32,
+    index: usize,
+    moves: usize,
+}
+impl MoveFix for Tracked {
+    unsafe fn fix_shift<S>(storage: &mut S, shifted_from: usize, shifted_by: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        for index in shifted_from..storage.len() {
+            if storage.contains_index(index) {
+                let element = storage.get_mut(index).unwrap();
+                element.index = (element.index as isize + shifted_by.get()) as usize;
+            }
+        }
+    }
+    unsafe fn fix_move<S>(storage: &mut S, previous_index: usize, current_index: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+        let element = storage.get_mut(current_index).unwrap();
+        assert_eq!(element.index, previous_index);
+        element.index = current_index;
+        element.moves += 1;
+    }
+}
+
+fn tracked_with_holes(len: u32, holes: &[usize]) -> SparseVec<Tracked> {
+    let mut storage: SparseVec<Tracked> = ListStorage::new();
+    for value in 0..len {
+        let index = ListStorage::add(
+            &mut storage,
+            Tracked {
+                value,
+                index: 0,
+                moves: 0,
+            },
+        );
+        ListStorage::get_mut(&mut storage, index).unwrap().index = index;
+    }
+    for &index in holes {
+        storage.remove_and_shiftfix(index);
+    }
+    storage
+}
+
+/// Checks that every element knows its index and returns the values and move counts of the elements in order.
+fn tracked_state(storage: &SparseVec<Tracked>) -> Vec<(u32, usize)> {
+    IntoRefIterator::iter(storage)
+        .indexed()
+        .map(|(index, element)| {
+            assert_eq!(element.index, index);
+            (element.value, element.moves)
+        })
+        .collect()
+}
+
+#[test]
+fn defragment_fills_holes_from_the_back() {
+    let mut storage = tracked_with_holes(10, &[1, 2, 5, 9]);
+    storage.defragment_and_fix();
+    assert!(storage.is_dense());
+    assert_eq!(ListStorage::len(&storage), 6);
+    // Every relocated element is notified exactly once, everything else is left alone
+    assert_eq!(
+        tracked_state(&storage),
+        [(0, 0), (8, 1), (7, 1), (3, 0), (4, 0), (6, 1)]
+    );
+}
+
+#[test]
+fn defragment_handles_edge_cases() {
+    let mut storage = tracked_with_holes(4, &[]);
+    storage.defragment_and_fix();
+    assert_eq!(tracked_state(&storage), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+
+    // Trailing holes only need to be cut off
+    let mut storage = tracked_with_holes(5, &[3, 4]);
+    storage.defragment_and_fix();
+    assert_eq!(tracked_state(&storage), [(0, 0), (1, 0), (2, 0)]);
+
+    let mut storage = tracked_with_holes(3, &[0, 1, 2]);
+    storage.defragment_and_fix();
+    assert_eq!(ListStorage::len(&storage), 0);
+
+    let mut storage = tracked_with_holes(1000, &(0..1000).step_by(2).collect::<Vec<_>>());
+    storage.defragment_and_fix();
+    let state = tracked_state(&storage);
+    assert_eq!(state.len(), 500);
+    assert!(state.iter().all(|&(_, moves)| moves <= 1));
+    let mut values = state.iter().map(|&(value, _)| value).collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, (1..1000).step_by(2).collect::<Vec<_>>());
+}
+
+#[test]
+fn remap_maps_old_indices_to_new_ones() {
+    let mut storage = with_holes(10, &[1, 2, 5, 9]);
+    let before = IntoRefIterator::iter(&storage)
+        .indexed()
+        .map(|(i, x)| (i, x.0))
+        .collect::<Vec<_>>();
+    let remap = storage.defragment_with_remap();
+    assert_eq!(remap.old_len(), 10);
+    assert_eq!(remap.new_len(), 6);
+    assert_eq!(remap.num_moves(), 3);
+    for (old, value) in before {
+        let new = remap.get(old).unwrap();
+        assert_eq!(ListStorage::get(&storage, new).unwrap().0, value);
+    }
+    for &hole in &[1, 2, 5, 9, 10, 100] {
+        assert_eq!(remap.get(hole), None);
+    }
+    for (old, new) in remap.moves() {
+        assert_eq!(remap.get(old), Some(new));
+        assert_eq!(ListStorage::get(&storage, new).unwrap().0 as usize, old);
+    }
+}
+
+#[test]
+fn remap_of_dense_storage_is_identity() {
+    let mut storage = with_holes(3, &[]);
+    let remap = storage.defragment_with_remap();
+    assert!(remap.is_identity());
+    assert_eq!(
+        (0..4).map(|i| remap.get(i)).collect::<Vec<_>>(),
+        [Some(0), Some(1), Some(2), None]
+    );
+
+    let mut storage = with_holes(4, &[2, 3]);
+    let remap = storage.defragment_with_remap();
+    assert!(remap.is_identity());
+    assert_eq!(remap.get(1), Some(1));
+    assert_eq!(remap.get(2), None);
+    assert_eq!(ListStorage::len(&storage), 2);
+}
+
+#[test]
+fn defragment_step_respects_the_budget() {
+    let mut storage = tracked_with_holes(12, &[0, 2, 4, 6]);
+    assert!(!storage.defragment_step(0));
+    assert_eq!(storage.num_holes(), 4);
+    assert!(!storage.defragment_step(1));
+    assert_eq!(storage.num_holes(), 3);
+    assert_eq!(ListStorage::len(&storage), 11);
+    tracked_state(&storage);
+    assert!(!storage.defragment_step(2));
+    assert_eq!(storage.num_holes(), 1);
+    assert!(storage.defragment_step(5));
+    assert!(storage.is_dense());
+    assert_eq!(ListStorage::len(&storage), 8);
+    let state = tracked_state(&storage);
+    assert!(state.iter().all(|&(_, moves)| moves <= 1));
+    let mut values = state.iter().map(|&(value, _)| value).collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, [1, 3, 5, 7, 8, 9, 10, 11]);
+}
+
+#[test]
+fn defragment_step_interleaves_with_other_operations() {
+    let mut storage = tracked_with_holes(10, &[1, 3, 5, 8]);
+    let mut next = 10;
+    while !storage.defragment_step(1) {
+        // Punch a hole and fill another one between every step
+        let victim = IntoRefIterator::iter(&storage)
+            .indexed()
+            .map(|(i, _)| i)
+            .nth(1)
+            .unwrap();
+        storage.remove_and_shiftfix(victim);
+        let index = ListStorage::add(
+            &mut storage,
+            Tracked {
+                value: next,
+                index: 0,
+                moves: 0,
+            },
+        );
+        ListStorage::get_mut(&mut storage, index).unwrap().index = index;
+        next += 1;
+        tracked_state(&storage);
+    }
+    assert!(storage.is_dense());
+    assert_eq!(tracked_state(&storage).len(), 6);
+}
+
+#[test]
+fn defragment_step_pops_trailing_holes_for_free() {
+    let mut storage = tracked_with_holes(6, &[3, 4, 5]);
+    assert!(storage.defragment_step(0));
+    assert_eq!(ListStorage::len(&storage), 3);
+    let mut storage = tracked_with_holes(6, &[0, 4, 5]);
+    assert!(storage.defragment_step(1));
+    assert_eq!(tracked_state(&storage), [(3, 1), (1, 0), (2, 0)]);
+}
+
+#[test]
+fn hole_ratio_policy_defragments_with_fixing() {
+    let mut storage = tracked_with_holes(8, &[]);
+    storage.set_defrag_policy(DefragPolicy::hole_ratio(1, 4));
+    assert_eq!(storage.defrag_policy(), DefragPolicy::hole_ratio(1, 4));
+    storage.remove_and_shiftfix(1);
+    storage.remove_and_shiftfix(2);
+    assert_eq!(storage.num_holes(), 2);
+    // 3/8 exceeds 1/4
+    storage.remove_and_shiftfix(3);
+    assert!(storage.is_dense());
+    assert_eq!(ListStorage::len(&storage), 5);
+    assert_eq!(
+        tracked_state(&storage),
+        [(0, 0), (7, 1), (6, 1), (5, 1), (4, 0)]
+    );
+}
+
+#[test]
+fn trailing_holes_policy_never_moves_elements() {
+    let mut storage = tracked_with_holes(6, &[]);
+    storage.set_defrag_policy(DefragPolicy::TrailingHoles(1));
+    storage.remove_and_shiftfix(2);
+    storage.remove_and_shiftfix(5);
+    assert_eq!(ListStorage::len(&storage), 6);
+    storage.remove_and_shiftfix(4);
+    assert_eq!(ListStorage::len(&storage), 4);
+    assert_eq!(storage.num_holes(), 1);
+    storage.remove_and_shiftfix(3);
+    // The hole before the removed elements is now trailing too
+    assert_eq!(ListStorage::len(&storage), 2);
+    assert!(storage.is_dense());
+    assert_eq!(tracked_state(&storage), [(0, 0), (1, 0)]);
+
+    storage.set_defrag_policy(DefragPolicy::TrailingHoles(0));
+    storage.remove_and_shiftfix(0);
+    assert_eq!(ListStorage::len(&storage), 2);
+    storage.remove_and_shiftfix(1);
+    assert_eq!(ListStorage::len(&storage), 0);
+}
+
+#[test]
+fn never_policy_is_the_default() {
+    let mut storage = with_holes(4, &[0, 1, 2, 3]);
+    assert_eq!(storage.defrag_policy(), DefragPolicy::Never);
+    assert_eq!(storage.num_holes(), 4);
+    storage.set_defrag_policy(DefragPolicy::hole_ratio(0, 1));
+    // Policies only kick in when a hole is punched
+    assert_eq!(storage.num_holes(), 4);
+    assert!(std::panic::catch_unwind(|| DefragPolicy::hole_ratio(1, 0)).is_err());
+}
+
+#[test]
+fn lowest_index_reuse_fills_the_lowest_hole() {
+    let holes = [190, 3, 150, 64, 63, 129, 0];
+    let mut storage = with_holes(200, &holes);
+    storage.set_hole_reuse(HoleReuse::LowestIndex);
+    assert_eq!(storage.hole_reuse(), HoleReuse::LowestIndex);
+    let mut sorted = holes.to_vec();
+    sorted.sort_unstable();
+    for &hole in &sorted {
+        assert_eq!(ListStorage::add(&mut storage, 1000.into()), hole);
+    }
+    assert!(storage.is_dense());
+    assert_eq!(ListStorage::add(&mut storage, 1000.into()), 200);
+
+    // Shifting the elements shifts the holes along with them
+    storage.remove_and_shiftfix(70);
+    storage.remove_and_shiftfix(10);
+    ListStorage::insert(&mut storage, 0, 2000.into());
+    ListStorage::remove(&mut storage, 100);
+    assert_eq!(ListStorage::add(&mut storage, 3000.into()), 11);
+    assert_eq!(ListStorage::add(&mut storage, 3000.into()), 71);
+    assert!(storage.is_dense());
+}
+
+#[test]
+fn hole_reuse_strategies_can_be_switched() {
+    let mut storage = linked_with_holes(10, &[5, 1, 8, 3]);
+    storage.set_hole_reuse(HoleReuse::LowestIndex);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 1);
+    // The other strategies still see the holes in the order in which they were punched
+    storage.set_hole_reuse(HoleReuse::Lifo);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 3);
+    storage.set_hole_reuse(HoleReuse::Fifo);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 5);
+    storage.remove_and_shiftfix(0);
+    storage.set_hole_reuse(HoleReuse::LowestIndex);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 0);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 8);
+    assert!(storage.is_dense());
+    assert_eq!(
+        linked_values(&storage),
+        [100, 100, 2, 100, 4, 100, 6, 7, 100, 9]
+    );
+}
+
+#[test]
+fn insert_shifts_the_holes_after_it() {
+    let mut storage = linked_with_holes(6, &[4, 1]);
+    ListStorage::insert(&mut storage, 2, linked(100));
+    assert!(storage.is_hole(1));
+    assert!(storage.is_hole(5));
+    assert_eq!(storage.num_holes(), 2);
+    // The hole list still remembers that the hole which is now at 5 was punched first
+    assert_eq!(ListStorage::add(&mut storage, linked(200)), 5);
+    assert_eq!(ListStorage::add(&mut storage, linked(200)), 1);
+    assert_eq!(ListStorage::add(&mut storage, linked(200)), 7);
+
+    // Same with the links in the link table
+    let mut storage = table_with_holes(6, &[4, 1]);
+    ListStorage::insert(&mut storage, 2, 100.into());
+    assert!(storage.is_hole(1));
+    assert!(storage.is_hole(5));
+    assert_eq!(ListStorage::add(&mut storage, 200.into()), 5);
+    assert_eq!(ListStorage::add(&mut storage, 200.into()), 1);
+    assert_eq!(values(&storage), [0, 200, 100, 2, 3, 200, 5]);
+}
+
+#[test]
+fn insert_and_shiftfix_fixes_elements_and_holes() {
+    let mut storage = tracked_with_holes(6, &[1, 4]);
+    ListStorage::insert_and_shiftfix(
+        &mut storage,
+        0,
+        Tracked {
+            value: 100,
+            index: 0,
+            moves: 0,
+        },
+    );
+    // Tracked::fix_shift walks the storage by position, so it also bumps the inserted element
+    ListStorage::get_mut(&mut storage, 0).unwrap().index = 0;
+    assert_eq!(
+        tracked_state(&storage),
+        [(100, 0), (0, 0), (2, 0), (3, 0), (5, 0)]
+    );
+    assert!(storage.is_hole(2));
+    assert!(storage.is_hole(5));
+    storage.set_hole_reuse(HoleReuse::Lifo);
+    for expected in [5, 2].iter() {
+        let index = ListStorage::add(
+            &mut storage,
+            Tracked {
+                value: 200,
+                index: *expected,
+                moves: 0,
+            },
+        );
+        assert_eq!(index, *expected);
+    }
+    assert!(storage.is_dense());
+    assert_eq!(tracked_state(&storage).len(), 7);
+}
+
+#[test]
+fn truncate_forgets_the_holes_it_cuts_off() {
+    let mut storage = with_holes(8, &[6, 1, 5]);
+    ListStorage::truncate(&mut storage, 6);
+    assert_eq!(storage.num_holes(), 2);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 1);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 5);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 6);
+
+    let mut storage = with_holes(8, &[2, 7, 4]);
+    ListStorage::truncate(&mut storage, 3);
+    assert_eq!(storage.num_holes(), 1);
+    ListStorage::truncate(&mut storage, 2);
+    assert!(storage.is_dense());
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 2);
+    assert_eq!(values(&storage), [0, 1, 100]);
+}
+
+#[test]
+fn raw_remove_shifts_the_holes_after_it() {
+    let mut storage = linked_with_holes(7, &[5, 1]);
+    assert_eq!(ListStorage::remove(&mut storage, 3).0, [3, 0]);
+    assert_eq!(ListStorage::len(&storage), 6);
+    assert!(storage.is_hole(1));
+    assert!(storage.is_hole(4));
+    assert_eq!(linked_values(&storage), [0, 2, 4, 6]);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 4);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 1);
+    assert!(storage.is_dense());
+    // Same with the links in the link table
+    let mut storage = table_with_holes(7, &[5, 1]);
+    assert_eq!(ListStorage::remove(&mut storage, 3).0, 3);
+    assert_eq!(values(&storage), [0, 2, 4, 6]);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 4);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 1);
+    assert!(storage.is_dense());
+    // Removing before the first hole and after the last one works too
+    let mut storage = with_holes(5, &[2]);
+    assert_eq!(ListStorage::remove(&mut storage, 4).0, 4);
+    assert_eq!(ListStorage::remove(&mut storage, 0).0, 0);
+    assert!(storage.is_hole(1));
+    assert_eq!(values(&storage), [1, 3]);
+}
+
+#[test]
+#[should_panic(expected = "hole")]
+fn raw_remove_of_a_hole_panics() {
+    let mut storage = with_holes(3, &[1]);
+    ListStorage::remove(&mut storage, 1);
+}
+
+#[test]
+fn pop_discards_trailing_holes() {
+    let mut storage = with_holes(6, &[4, 1, 5]);
+    assert_eq!(ListStorage::pop(&mut storage).map(|x| x.0), Some(3));
+    assert_eq!(ListStorage::len(&storage), 3);
+    assert_eq!(storage.num_holes(), 1);
+    assert_eq!(ListStorage::pop(&mut storage).map(|x| x.0), Some(2));
+    assert_eq!(ListStorage::pop(&mut storage).map(|x| x.0), Some(0));
+    assert_eq!(ListStorage::len(&storage), 0);
+    assert!(storage.is_dense());
+    assert_eq!(ListStorage::pop(&mut storage).map(|x| x.0), None);
+    assert_eq!(ListStorage::add(&mut storage, 100.into()), 0);
+}
+
+/// Drains a list from the back using nothing but the `ListStorage` interface.
+fn drain_generic<S: ListStorage<Element = Element>>(storage: &mut S) -> Vec<u32> {
+    let mut drained = Vec::new();
+    while let Some(element) = storage.pop() {
+        drained.push(element.0);
+        if storage.len() > 2 {
+            drained.push(storage.remove(0).0);
+        }
+    }
+    drained
+}
+
+#[test]
+fn sparse_storage_works_in_generic_code() {
+    let mut storage = with_holes(8, &[6, 2, 7, 3]);
+    assert_eq!(drain_generic(&mut storage), [5, 0, 4, 1]);
+    assert_eq!(ListStorage::len(&storage), 0);
+    assert!(storage.is_dense());
+}
+
+#[test]
+fn split_off_and_append_move_the_holes_along() {
+    let mut storage = table_with_holes(8, &[6, 1, 4]);
+    let mut tail = ListStorage::split_off(&mut storage, 3);
+    assert_eq!(ListStorage::len(&storage), 3);
+    assert_eq!(storage.num_holes(), 1);
+    assert_eq!(ListStorage::len(&tail), 5);
+    assert_eq!(tail.num_holes(), 2);
+    assert!(tail.is_hole(1));
+    assert!(tail.is_hole(3));
+    assert_eq!(values(&tail), [3, 5, 7]);
+    // The holes which were moved out are linked in the order of their indices
+    assert_eq!(tail.hole_reuse(), HoleReuse::Fifo);
+    assert_eq!(ListStorage::add(&mut tail, 100.into()), 1);
+
+    ListStorage::append(&mut storage, &mut tail);
+    assert!(ListStorage::is_empty(&tail));
+    assert!(tail.is_dense());
+    assert_eq!(ListStorage::len(&storage), 8);
+    assert!(storage.is_hole(1));
+    assert!(storage.is_hole(6));
+    assert_eq!(values(&storage), [0, 2, 3, 100, 5, 7]);
+    // The holes of the appended storage go after the ones which were already there
+    assert_eq!(ListStorage::add(&mut storage, 200.into()), 1);
+    assert_eq!(ListStorage::add(&mut storage, 200.into()), 6);
+    assert!(storage.is_dense());
+
+    // Same with the links stored in the holes themselves
+    let mut storage = linked_with_holes(6, &[4, 1]);
+    let mut tail = ListStorage::split_off(&mut storage, 2);
+    assert_eq!(storage.num_holes(), 1);
+    assert_eq!(
+        tail.slot(2),
+        Some(SlotRef::Hole {
+            prev: None,
+            next: None
+        })
+    );
+    tail.remove_and_shiftfix(0);
+    ListStorage::append(&mut storage, &mut tail);
+    assert_eq!(linked_values(&storage), [0, 3, 5]);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 1);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 2);
+    assert_eq!(ListStorage::add(&mut storage, linked(100)), 4);
+    assert!(storage.is_dense());
+}
+
+#[test]
+fn slots_can_be_inspected_without_panicking() {
+    let storage = linked_with_holes(5, &[3, 1]);
+    assert_eq!(storage.slot(0), Some(SlotRef::Element(&linked(0))));
+    assert_eq!(
+        storage.slot(3),
+        Some(SlotRef::Hole {
+            prev: None,
+            next: Some(1)
+        })
+    );
+    assert_eq!(
+        storage.slot(1),
+        Some(SlotRef::Hole {
+            prev: Some(3),
+            next: None
+        })
+    );
+    assert_eq!(storage.slot(5), None);
+
+    // Holes between small elements are linked through the link table
+    let mut storage = table_with_holes(5, &[3, 1]);
+    assert_eq!(storage.slot(0), Some(SlotRef::Element(&0.into())));
+    assert_eq!(
+        storage.slot(3),
+        Some(SlotRef::Hole {
+            prev: None,
+            next: Some(1)
+        })
+    );
+    assert_eq!(storage.slot(5), None);
+
+    assert!(storage.is_hole(1));
+    assert!(!storage.is_hole(2));
+    assert!(!storage.is_hole(5));
+    assert_eq!(storage.try_get(1), None);
+    assert_eq!(storage.try_get(4), Some(&4.into()));
+    assert_eq!(storage.try_get(5), None);
+
+    *storage.try_get_mut(2).unwrap() = 20.into();
+    assert!(storage.try_get_mut(3).is_none());
+    assert!(storage.try_get_mut(5).is_none());
+    assert_eq!(values(&storage), [0, 20, 4]);
+    ListStorage::add(&mut storage, 30.into());
+    assert_eq!(storage.try_get(3), Some(&30.into()));
+    assert_eq!(
+        storage.slot(1),
+        Some(SlotRef::Hole {
+            prev: None,
+            next: None
+        })
+    );
+
+    // Taking an element out leaves a hole instead of panicking on one
+    assert_eq!(storage.take(2), Some(20.into()));
+    assert_eq!(storage.take(2), None);
+    assert_eq!(storage.take(5), None);
+    assert!(storage.is_hole(2));
+    assert_eq!(values(&storage), [0, 30, 4]);
+}
+
+/// The slot layout used before holes were moved into the occupancy bitmap: a tag plus either the element or the link to the next hole.
+#[allow(dead_code)]
+enum SlotEnumBased<T> {
+    Element(T),
+    Hole(Option<usize>),
+}
+
+#[test]
+fn slots_are_smaller_than_the_tagged_layout() {
+    use core::mem::size_of;
+    use granite::SparseStorageSlot as Slot;
+    assert_eq!(size_of::<Slot<u8>>(), size_of::<u8>());
+    assert_eq!(size_of::<Slot<u32>>(), size_of::<u32>());
+    assert_eq!(size_of::<Slot<u64>>(), size_of::<u64>());
+    assert_eq!(size_of::<Slot<[usize; 4]>>(), size_of::<[usize; 4]>());
+    assert!(size_of::<Slot<u32>>() < size_of::<SlotEnumBased<u32>>());
+    assert!(size_of::<Slot<u64>>() < size_of::<SlotEnumBased<u64>>());
+    assert!(size_of::<Slot<[usize; 4]>>() < size_of::<SlotEnumBased<[usize; 4]>>());
+}
+
+/// Measures the whole storage, including the occupancy bitmap and the link table, by keeping all of them inline in fixed-capacity storages.
+#[cfg(feature = "arrayvec")]
+#[test]
+fn small_elements_take_less_memory_than_the_tagged_layout() {
+    use arrayvec::ArrayVec;
+    use core::mem::size_of;
+    use granite::{SparseStorage, SparseStorageSlot as Slot};
+    // 256 bits take 4 words, and their summary takes one more
+    type Fixed = SparseStorage<Element, ArrayVec<[Slot<Element>; 256]>, ArrayVec<[usize; 4]>>;
+
+    let mut storage: Fixed = ListStorage::new();
+    for value in 0..256 {
+        ListStorage::add(&mut storage, value.into());
+    }
+    // Holes anywhere in the storage don't need any room besides the bitmap
+    for &index in &[254, 0, 128, 255] {
+        storage.remove_and_shiftfix(index);
+    }
+    assert_eq!(storage.num_holes(), 4);
+    assert_eq!(
+        storage.slot(128),
+        Some(SlotRef::Hole {
+            prev: None,
+            next: None
+        })
+    );
+    for &index in &[0, 128, 254, 255] {
+        assert_eq!(ListStorage::add(&mut storage, 1000.into()), index);
+    }
+    assert!(storage.is_dense());
+
+    let total = size_of::<Fixed>();
+    let elements = size_of::<[Element; 256]>();
+    let tagged = size_of::<[SlotEnumBased<u32>; 256]>();
+    // The slots, 3 word storages of 4 words each, their lengths and a few fields of bookkeeping
+    assert!(total - elements <= 24 * size_of::<usize>());
+    assert!(total < tagged / 2);
+}
+
+/// An element which counts how many of its kind are alive through a shared counter.
+struct Counted<'a, T> {
+    payload: T,
+    alive: &'a Cell<usize>,
+}
+impl<'a, T> Counted<'a, T> {
+    fn new(payload: T, alive: &'a Cell<usize>) -> Self {
+        alive.set(alive.get() + 1);
+        Self { payload, alive }
+    }
+}
+impl<'a, T: Clone> Clone for Counted<'a, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.payload.clone(), self.alive)
+    }
+}
+impl<'a, T> Drop for Counted<'a, T> {
+    fn drop(&mut self) {
+        self.alive.set(self.alive.get() - 1);
+    }
+}
+impl<'a, T> MoveFix for Counted<'a, T> {
+    unsafe fn fix_shift<S>(_: &mut S, _: usize, _: NonZeroIsize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+    }
+    unsafe fn fix_move<S>(_: &mut S, _: usize, _: usize)
+    where
+        S: ListStorage<Element = Self>,
+    {
+    }
+}
+
+/// Runs every operation which drops or moves elements against a storage of `Counted<T>` and checks that each element is dropped exactly once.
+fn check_drops<T: Clone + Default>() {
+    let alive = Cell::new(0);
+    let new = |alive| Counted::new(T::default(), alive);
+    {
+        let mut storage: SparseVec<Counted<'_, T>> = ListStorage::new();
+        for _ in 0..20 {
+            ListStorage::add(&mut storage, new(&alive));
+        }
+        assert_eq!(alive.get(), 20);
+
+        // Punching holes drops the removed elements right away
+        drop(storage.remove_and_shiftfix(3));
+        drop(storage.remove_and_shiftfix(7));
+        drop(storage.remove_and_shiftfix(8));
+        drop(storage.remove_and_shiftfix(19));
+        assert_eq!(alive.get(), 16);
+
+        // Raw removal and insertion shift the holes without touching them
+        drop(ListStorage::remove(&mut storage, 0));
+        ListStorage::insert(&mut storage, 1, new(&alive));
+        assert_eq!(alive.get(), 16);
+
+        // Popping skips the trailing hole
+        drop(ListStorage::pop(&mut storage));
+        assert_eq!(alive.get(), 15);
+
+        // Filling a hole doesn't drop whatever used to be there
+        ListStorage::add(&mut storage, new(&alive));
+        assert_eq!(alive.get(), 16);
+
+        let cloned = storage.clone();
+        assert_eq!(alive.get(), 32);
+        drop(cloned);
+        assert_eq!(alive.get(), 16);
+
+        // Splitting and appending moves the elements without dropping or duplicating any
+        let mut tail = ListStorage::split_off(&mut storage, 10);
+        assert_eq!(alive.get(), 16);
+        ListStorage::append(&mut storage, &mut tail);
+        drop(tail);
+        assert_eq!(alive.get(), 16);
+
+        // Truncating drops the elements it cuts off, but not the holes
+        drop(storage.remove_and_shiftfix(12));
+        ListStorage::truncate(&mut storage, 14);
+        assert_eq!(alive.get(), 11);
+
+        storage.remove_and_shiftfix(0);
+        storage.remove_and_shiftfix(5);
+        storage.defragment();
+        assert!(storage.is_dense());
+        assert_eq!(alive.get(), 9);
+
+        // Dropping the storage with holes in it drops only the elements
+        storage.remove_and_shiftfix(2);
+        storage.remove_and_shiftfix(4);
+        assert_eq!(alive.get(), 7);
+    }
+    assert_eq!(alive.get(), 0);
+}
+
+#[test]
+fn small_elements_are_dropped_exactly_once() {
+    check_drops::<()>();
+}
+
+#[test]
+fn linked_elements_are_dropped_exactly_once() {
+    check_drops::<[usize; 4]>();
+}
+
+#[test]
+fn into_inner_leaks_elements_but_not_the_storage() {
+    let alive = Cell::new(0);
+    let mut storage: SparseVec<Counted<'_, ()>> = ListStorage::new();
+    for _ in 0..4 {
+        ListStorage::add(&mut storage, Counted::new((), &alive));
+    }
+    storage.remove_and_shiftfix(1);
+    let inner = storage.into_inner();
+    assert_eq!(inner.len(), 4);
+    drop(inner);
+    assert_eq!(alive.get(), 3);
+}
@@ -0,0 +1,208 @@
+#![cfg(feature = "alloc")]
+
+use granite::{SparseChainKey as Key, SparseChainVec, Storage, StorageIter};
+
+fn key(storage: usize, index: usize) -> Key {
+    Key { storage, index }
+}
+
+/// Creates a sparse chain with the specified limit and the specified amount of elements, each equal to the order in which it was added, returning it along with the keys.
+fn chain_of(limit: usize, len: u32) -> (SparseChainVec<u32>, Vec<Key>) {
+    let mut storage = SparseChainVec::new();
+    storage.set_limit(limit);
+    let keys = (0..len).map(|value| storage.add(value)).collect();
+    (storage, keys)
+}
+
+#[test]
+fn keys_stay_valid_across_removals() {
+    let (mut storage, keys) = chain_of(4, 10);
+    assert_eq!(storage.num_storages(), 3);
+    assert_eq!(keys[5], key(1, 1));
+    for &index in &[0, 5, 6, 9] {
+        assert_eq!(storage.remove(&keys[index]), index as u32);
+    }
+    assert_eq!(storage.len(), 6);
+    assert_eq!(storage.num_holes(), 4);
+    for (value, key) in keys.iter().enumerate() {
+        let expected = Some(value as u32).filter(|value| ![0, 5, 6, 9].contains(value));
+        assert_eq!(storage.get(key).copied(), expected);
+        assert_eq!(storage.contains_key(key), expected.is_some());
+    }
+    *storage.get_mut(&keys[7]).unwrap() = 70;
+    assert_eq!(storage.get(&keys[7]), Some(&70));
+    assert_eq!(storage.get(&key(10, 0)), None);
+    assert_eq!(storage.get(&key(0, 10)), None);
+}
+
+#[test]
+#[should_panic(expected = "does not point to an element")]
+fn removing_twice_panics() {
+    let (mut storage, keys) = chain_of(4, 10);
+    storage.remove(&keys[3]);
+    storage.remove(&keys[3]);
+}
+
+#[test]
+fn holes_in_earlier_storages_are_filled_first() {
+    let (mut storage, keys) = chain_of(4, 10);
+    storage.remove(&keys[9]);
+    storage.remove(&keys[6]);
+    storage.remove(&keys[1]);
+    assert_eq!(storage.add(100), keys[1]);
+    assert_eq!(storage.add(100), keys[6]);
+    assert_eq!(storage.add(100), keys[9]);
+    // The last storage still has room before a new one is needed
+    assert_eq!(storage.add(100), key(2, 2));
+    assert_eq!(storage.add(100), key(2, 3));
+    assert_eq!(storage.add(100), key(3, 0));
+    assert_eq!(storage.num_holes(), 0);
+    assert_eq!(storage.len(), 13);
+
+    // Raising the limit makes room in the storages which were full
+    storage.set_limit(6);
+    assert_eq!(storage.add(200), key(0, 4));
+}
+
+#[test]
+fn iteration_skips_holes() {
+    let (mut storage, keys) = chain_of(4, 10);
+    for &index in &[0, 3, 4, 9] {
+        storage.remove(&keys[index]);
+    }
+    let iterated = StorageIter::iter(&storage)
+        .map(|(key, &value)| (key, value))
+        .collect::<Vec<_>>();
+    let expected = [1, 2, 5, 6, 7, 8]
+        .iter()
+        .map(|&index| (keys[index], index as u32))
+        .collect::<Vec<_>>();
+    assert_eq!(iterated, expected);
+    assert_eq!(StorageIter::iter(&storage).len(), 6);
+    for value in storage.values_mut() {
+        *value *= 10;
+    }
+    assert_eq!(
+        storage.values().copied().collect::<Vec<_>>(),
+        [10, 20, 50, 60, 70, 80]
+    );
+    assert_eq!(
+        storage.keys().collect::<Vec<_>>(),
+        expected.iter().map(|&(key, _)| key).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reserve_counts_holes_as_room() {
+    let (mut storage, keys) = chain_of(4, 8);
+    storage.remove(&keys[2]);
+    storage.remove(&keys[7]);
+    storage.reserve(2);
+    assert_eq!(storage.num_storages(), 2);
+    storage.reserve(3);
+    assert_eq!(storage.num_storages(), 3);
+    assert_eq!(storage.try_reserve(6), Ok(()));
+    assert_eq!(storage.num_storages(), 3);
+    assert_eq!(storage.try_reserve(7), Ok(()));
+    assert_eq!(storage.num_storages(), 4);
+    // The holes take up slots which were already allocated
+    assert!(storage.capacity() >= 13);
+    for value in 0..7 {
+        storage.add(value);
+    }
+    assert_eq!(storage.num_storages(), 4);
+}
+
+#[test]
+fn reserve_allocates_new_storages_to_the_limit() {
+    let mut storage = SparseChainVec::<u32>::new();
+    storage.set_limit(4);
+    storage.reserve(5);
+    assert_eq!(storage.num_storages(), 2);
+    assert!(storage.capacity() >= 8);
+    let (mut storage, _) = chain_of(4, 2);
+    assert_eq!(storage.try_reserve(3), Ok(()));
+    assert_eq!(storage.num_storages(), 2);
+    assert!(storage.capacity() >= 8);
+}
+
+#[test]
+fn keys_convert_to_positions_in_the_chain() {
+    let (mut storage, keys) = chain_of(4, 10);
+    storage.remove(&keys[2]);
+    storage.remove(&keys[5]);
+    // The holes still take up positions
+    assert_eq!(storage.position_of(&keys[6]), Some(6));
+    assert_eq!(storage.position_of(&keys[9]), Some(9));
+    assert_eq!(storage.position_of(&keys[5]), None);
+    assert_eq!(storage.key_at(6), Some(keys[6]));
+    assert_eq!(storage.key_at(5), None);
+    assert_eq!(storage.key_at(10), None);
+
+    let chain = storage.as_chain();
+    assert_eq!(chain.num_storages(), 3);
+    let elements = chain.elements();
+    assert_eq!(elements.len(), 8);
+    assert_eq!(
+        elements.copied().collect::<Vec<_>>(),
+        [0, 1, 3, 4, 6, 7, 8, 9]
+    );
+}
+
+#[test]
+fn shrink_to_fit_only_removes_storages_of_holes_at_the_end() {
+    let (mut storage, keys) = chain_of(4, 12);
+    storage.remove(&keys[0]);
+    for key in &keys[8..] {
+        storage.remove(key);
+    }
+    storage.shrink_to_fit();
+    assert_eq!(storage.num_storages(), 2);
+    // The holes at the start are kept, so the keys of the other elements stay valid
+    assert_eq!(storage.get(&keys[7]), Some(&7));
+    assert_eq!(storage.add(100), keys[0]);
+    assert_eq!(storage.add(100), key(2, 0));
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn fixed_capacity_storages_are_never_overfilled() {
+    use arrayvec::ArrayVec;
+    use granite::{CapacityError, SparseChain, SparseStorage, SparseStorageSlot as Slot};
+
+    // The word storages also hold the links of the holes, which take two words per slot
+    type Inner = SparseStorage<u32, ArrayVec<[Slot<u32>; 4]>, ArrayVec<[usize; 8]>>;
+    type Fixed = SparseChain<
+        u32,
+        ArrayVec<[Slot<u32>; 4]>,
+        ArrayVec<[usize; 8]>,
+        ArrayVec<[Inner; 2]>,
+        ArrayVec<[usize; 2]>,
+    >;
+
+    let mut storage = Fixed::new();
+    // The limit is higher than the storages can hold
+    storage.set_limit(64);
+    assert_eq!(storage.try_reserve(9), Err(CapacityError::new(())));
+    assert_eq!(storage.num_storages(), 0);
+    assert_eq!(storage.try_reserve(8), Ok(()));
+    assert_eq!(storage.num_storages(), 2);
+    for value in 0..8 {
+        assert_eq!(
+            storage.try_add(value),
+            Ok(key(value as usize / 4, value as usize % 4))
+        );
+    }
+    assert_eq!(storage.try_add(8), Err(CapacityError::new(8)));
+    assert_eq!(storage.try_reserve(1), Err(CapacityError::new(())));
+    assert_eq!(storage.len(), 8);
+
+    let mut storage = Fixed::new();
+    storage.set_limit(64);
+    for value in 0..8 {
+        storage.add(value);
+    }
+    assert_eq!(storage.num_storages(), 2);
+    storage.remove(&key(1, 2));
+    assert_eq!(storage.try_add(100), Ok(key(1, 2)));
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "alloc")]
+
+use granite::{DummyMoveFix, ListStorage, SparseVec, Storage};
+
+#[test]
+fn list_storage_keys_are_indices() {
+    let mut list = vec![DummyMoveFix(1), DummyMoveFix(2), DummyMoveFix(3)];
+    assert!(Storage::contains_key(&list, &2));
+    assert!(!Storage::contains_key(&list, &3));
+    assert_eq!(Storage::get(&list, &1), Some(&DummyMoveFix(2)));
+    assert_eq!(Storage::get(&list, &3), None);
+    Storage::get_mut(&mut list, &0).unwrap().0 = 0;
+    assert_eq!(list, [0, 2, 3]);
+}
+
+#[test]
+fn sparse_holes_are_not_keys() {
+    let mut storage: SparseVec<DummyMoveFix<u32>> = ListStorage::new();
+    for value in 0..3 {
+        ListStorage::add(&mut storage, value.into());
+    }
+    storage.remove_and_shiftfix(1);
+    assert!(ListStorage::contains_index(&storage, 0));
+    assert!(!ListStorage::contains_index(&storage, 1));
+    assert!(!Storage::contains_key(&storage, &1));
+    // Used to panic on the hole instead of returning None
+    assert_eq!(Storage::get(&storage, &1), None);
+    assert_eq!(Storage::get_mut(&mut storage, &1), None);
+    assert_eq!(Storage::get(&storage, &2), Some(&DummyMoveFix(2)));
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "alloc")]
+
+use granite::{BitSparseVec, Chain, DummyMoveFix, ListStorage, SparseVec, StorageIter};
+
+type Element = DummyMoveFix<u32>;
+
+/// Holds on to all mutable references at once before writing through them, which is what the iterator has to allow without any of them aliasing.
+fn bump_all<'a, S>(storage: &'a mut S) -> Vec<S::Key>
+where
+    S: StorageIter<'a, Element = Element>,
+{
+    let items = StorageIter::iter_mut(storage).collect::<Vec<_>>();
+    let mut keys = Vec::new();
+    for (key, element) in items {
+        element.0 += 100;
+        keys.push(key);
+    }
+    keys
+}
+
+#[test]
+fn list_iter_mut_pairs_indices() {
+    let mut list = (0..5).map(DummyMoveFix).collect::<Vec<Element>>();
+    assert_eq!(bump_all(&mut list), [0, 1, 2, 3, 4]);
+    assert_eq!(list, [100, 101, 102, 103, 104]);
+    let reversed = StorageIter::iter(&list)
+        .rev()
+        .map(|(index, element)| (index, element.0))
+        .collect::<Vec<_>>();
+    assert_eq!(reversed, [(4, 104), (3, 103), (2, 102), (1, 101), (0, 100)]);
+    let mut iter = StorageIter::iter_mut(&mut list);
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next_back().map(|(index, _)| index), Some(4));
+    assert_eq!(iter.next().map(|(index, _)| index), Some(0));
+    assert_eq!(iter.len(), 3);
+}
+
+#[test]
+fn sparse_iter_mut_skips_holes() {
+    let mut storage: SparseVec<Element> = ListStorage::new();
+    for value in 0..6 {
+        ListStorage::add(&mut storage, value.into());
+    }
+    storage.remove_and_shiftfix(1);
+    storage.remove_and_shiftfix(4);
+    assert_eq!(bump_all(&mut storage), [0, 2, 3, 5]);
+    assert_eq!(storage.keys().collect::<Vec<_>>(), [0, 2, 3, 5]);
+    let values = storage.values().map(|x| x.0).collect::<Vec<_>>();
+    assert_eq!(values, [100, 102, 103, 105]);
+    let last = StorageIter::iter_mut(&mut storage).next_back();
+    assert_eq!(
+        last.map(|(index, element)| (index, element.0)),
+        Some((5, 105))
+    );
+}
+
+#[test]
+fn bit_sparse_iter_mut_skips_holes() {
+    let mut storage: BitSparseVec<Element> = ListStorage::new();
+    for value in 0..100 {
+        ListStorage::add(&mut storage, value.into());
+    }
+    for index in (0..100).filter(|index| index % 7 != 0) {
+        storage.remove_and_shiftfix(index);
+    }
+    let expected = (0..100).step_by(7).collect::<Vec<usize>>();
+    assert_eq!(bump_all(&mut storage), expected);
+    assert!(storage
+        .values()
+        .zip(&expected)
+        .all(|(element, &index)| element.0 as usize == index + 100));
+}
+
+#[test]
+fn chain_iter_mut_crosses_storages() {
+    let mut chain = Chain::<Element, Vec<Element>, Vec<Vec<Element>>, Vec<usize>>::new();
+    chain.set_limit(2);
+    for value in 0..5 {
+        chain.push(value.into());
+    }
+    assert_eq!(bump_all(&mut chain), [0, 1, 2, 3, 4]);
+    assert_eq!(
+        chain.values().map(|x| x.0).collect::<Vec<_>>(),
+        [100, 101, 102, 103, 104]
+    );
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "alloc")]
+
+use granite::{CapacityError, Chain, DummyMoveFix, ListStorage, SparseVec};
+
+type VecChain = Chain<u32, Vec<u32>, Vec<Vec<u32>>, Vec<usize>>;
+
+#[test]
+fn growable_storages_never_fail() {
+    let mut list: Vec<u32> = ListStorage::new();
+    assert_eq!(ListStorage::try_push(&mut list, 1), Ok(()));
+    assert_eq!(ListStorage::try_insert(&mut list, 0, 0), Ok(()));
+    assert_eq!(ListStorage::try_add(&mut list, 2), Ok(2));
+    assert_eq!(ListStorage::try_reserve(&mut list, 100), Ok(()));
+    assert!(list.capacity() >= 103);
+    assert_eq!(list, [0, 1, 2]);
+
+    let mut sparse: SparseVec<DummyMoveFix<u32>> = ListStorage::new();
+    assert_eq!(ListStorage::try_add(&mut sparse, 0.into()), Ok(0));
+    assert_eq!(ListStorage::try_add(&mut sparse, 1.into()), Ok(1));
+    sparse.remove_and_shiftfix(0);
+    // The hole gets reused
+    assert_eq!(ListStorage::try_add(&mut sparse, 2.into()), Ok(0));
+}
+
+#[test]
+fn chain_try_api_matches_infallible_one() {
+    let mut chain = VecChain::new();
+    chain.set_limit(4);
+    for value in 0..10 {
+        assert_eq!(chain.try_push(value), Ok(()));
+    }
+    assert_eq!(chain.try_insert(5, 100), Ok(()));
+    assert_eq!(chain.try_add(200), Ok(11));
+    assert_eq!(
+        chain.elements().copied().collect::<Vec<_>>(),
+        [0, 1, 2, 3, 4, 100, 5, 6, 7, 8, 9, 200],
+    );
+    assert_eq!(chain.try_reserve(9), Ok(()));
+    assert!(chain.capacity() >= 21);
+}
+
+#[test]
+fn capacity_error_hands_back_the_element() {
+    let error = CapacityError::new(String::from("element"));
+    assert_eq!(error.clone().map(|x| x.len()).element(), 7);
+    assert_eq!(error.clone().simplify(), CapacityError::new(()));
+    assert_eq!(error.element(), "element");
+}
+
+#[cfg(feature = "arrayvec")]
+mod fixed_capacity {
+    use arrayvec::ArrayVec;
+    use granite::{CapacityError, Chain, IntoRefIterator, ListStorage};
+
+    type Chunk = ArrayVec<[u8; 4]>;
+    /// Uses the default limit, which is way above the capacity of a chunk.
+    type ChunkChain = Chain<u8, Chunk, Vec<Chunk>, Vec<usize>>;
+    type FixedChain = Chain<u8, Chunk, ArrayVec<[Chunk; 2]>, ArrayVec<[usize; 2]>>;
+
+    #[test]
+    fn full_list_hands_back_the_element() {
+        let mut list: ArrayVec<[u8; 2]> = ListStorage::new();
+        assert_eq!(ListStorage::try_push(&mut list, 1), Ok(()));
+        assert_eq!(ListStorage::try_insert(&mut list, 0, 0), Ok(()));
+        assert_eq!(
+            ListStorage::try_push(&mut list, 2),
+            Err(CapacityError::new(2))
+        );
+        assert_eq!(
+            ListStorage::try_insert(&mut list, 0, 3),
+            Err(CapacityError::new(3))
+        );
+        assert_eq!(
+            ListStorage::try_add(&mut list, 4),
+            Err(CapacityError::new(4))
+        );
+        assert!(ListStorage::try_reserve(&mut list, 1).is_err());
+        assert_eq!(list.as_slice(), [0, 1]);
+    }
+
+    #[test]
+    fn chain_caps_chunks_at_their_capacity() {
+        let mut chain = ChunkChain::new();
+        assert!(chain.limit() > 4);
+        for value in 0..10 {
+            assert_eq!(chain.try_push(value), Ok(()));
+        }
+        chain.push(10);
+        assert_eq!(chain.num_storages(), 3);
+        assert_eq!(chain.try_insert(1, 100), Ok(()));
+        chain.insert(0, 200);
+        assert_eq!(
+            chain.elements().copied().collect::<Vec<_>>(),
+            [200, 0, 100, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+        );
+        assert!(chain
+            .iter()
+            .all(|st| IntoRefIterator::iter(&st).count() <= 4));
+    }
+
+    #[test]
+    fn chain_reserves_chunks_at_their_capacity() {
+        let mut chain = ChunkChain::new();
+        assert_eq!(chain.try_reserve(10), Ok(()));
+        assert_eq!(chain.num_storages(), 3);
+        assert_eq!(chain.capacity(), 12);
+        chain.reserve(13);
+        assert_eq!(chain.capacity(), 16);
+        let chain = ChunkChain::with_capacity(5);
+        assert_eq!(chain.num_storages(), 2);
+    }
+
+    #[test]
+    fn full_chain_hands_back_the_element() {
+        let mut chain = FixedChain::new();
+        for value in 0..8 {
+            assert_eq!(chain.try_push(value), Ok(()));
+        }
+        assert_eq!(chain.try_push(8), Err(CapacityError::new(8)));
+        assert_eq!(chain.try_insert(0, 9), Err(CapacityError::new(9)));
+        assert_eq!(chain.try_add(10), Err(CapacityError::new(10)));
+        assert_eq!(chain.len(), 8);
+        assert_eq!(
+            chain.elements().copied().collect::<Vec<_>>(),
+            [0, 1, 2, 3, 4, 5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn full_chain_refuses_to_reserve() {
+        let mut chain = FixedChain::new();
+        assert!(chain.try_reserve(9).is_err());
+        assert_eq!(chain.num_storages(), 0);
+        assert_eq!(chain.try_reserve(8), Ok(()));
+        assert_eq!(chain.capacity(), 8);
+        chain.push(0);
+        assert!(chain.try_reserve(8).is_err());
+        assert_eq!(chain.try_reserve(7), Ok(()));
+    }
+
+    #[test]
+    fn failed_reserve_leaves_the_chain_as_it_was() {
+        let mut chain = FixedChain::new();
+        chain.push(0);
+        // Rounding the amount of storages up must not overflow
+        assert!(chain.try_reserve(usize::MAX).is_err());
+        assert_eq!(chain.num_storages(), 1);
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain.try_reserve(7), Ok(()));
+        assert_eq!(chain.num_storages(), 2);
+    }
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn smallvec_reserves_on_the_heap() {
+    use smallvec::SmallVec;
+    let mut list: SmallVec<[u8; 2]> = ListStorage::new();
+    assert_eq!(ListStorage::try_reserve(&mut list, 100), Ok(()));
+    assert!(list.capacity() >= 100);
+    assert!(ListStorage::try_reserve(&mut list, usize::MAX).is_err());
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "alloc")]
+
+use granite::{SparseVec, Storage, Versioned, VersionedElement};
+
+type SparseVersioned = Versioned<u32, SparseVec<VersionedElement<u32>>>;
+
+#[test]
+fn stale_keys_are_rejected() {
+    let mut storage = SparseVersioned::new();
+    let first = storage.add(1);
+    let second = storage.add(2);
+    assert_eq!(storage.remove(&first), 1);
+    let reused = storage.add(3);
+    assert_eq!(reused.key, first.key);
+    assert_ne!(reused.generation, first.generation);
+
+    assert!(!storage.contains_key(&first));
+    assert_eq!(storage.get(&first), None);
+    assert_eq!(storage.get_mut(&first), None);
+    assert_eq!(storage.get(&reused), Some(&3));
+    *storage.get_mut(&second).unwrap() = 4;
+    assert_eq!(storage.get(&second), Some(&4));
+    assert_eq!(storage.len(), 2);
+}
+
+#[test]
+fn removed_key_without_reuse_is_rejected() {
+    let mut storage = SparseVersioned::new();
+    let key = storage.add(1);
+    storage.add(2);
+    storage.remove(&key);
+    // The slot is a hole in the inner storage now, rather than a different element
+    assert!(!storage.contains_key(&key));
+    assert_eq!(storage.get(&key), None);
+}
+
+#[test]
+#[should_panic(expected = "stale")]
+fn removing_stale_key_panics() {
+    let mut storage = SparseVersioned::new();
+    let key = storage.add(1);
+    storage.remove(&key);
+    storage.add(2);
+    storage.remove(&key);
+}
+
+#[cfg(feature = "slab")]
+#[test]
+fn works_over_slab() {
+    let mut storage = Versioned::<u32, slab::Slab<VersionedElement<u32>>>::new();
+    let old = storage.add(1);
+    storage.remove(&old);
+    let new = storage.add(2);
+    assert_eq!(old.key, new.key);
+    assert_eq!(storage.get(&old), None);
+    assert_eq!(storage.get(&new), Some(&2));
+}